@@ -67,6 +67,8 @@
 //! send(&EventType::Wheel {
 //!     delta_x: 0,
 //!     delta_y: 1,
+//!     precise_delta_x: 0.0,
+//!     precise_delta_y: 1.0,
 //! });
 //! ```
 //! # Main structs
@@ -126,11 +128,15 @@
 //!         x: f64,
 //!         y: f64,
 //!     },
-//!     /// Note: On Linux, there is no actual delta the actual values are ignored for delta_x
-//!     /// and we only look at the sign of delta_y to simulate wheelup or wheeldown.
+//!     /// Note: On Linux, there is no actual delta, we only look at the signs of delta_y
+//!     /// and delta_x to simulate wheelup/wheeldown or wheelleft/wheelright.
+//!     /// All deltas are in wheel notches, the precise ones fractional for high-resolution
+//!     /// devices, read through XInput 2.1 smooth scrolling on Linux.
 //!     Wheel {
 //!         delta_x: i64,
 //!         delta_y: i64,
+//!         precise_delta_x: f64,
+//!         precise_delta_y: f64,
 //!     },
 //! }
 //! ```
@@ -259,7 +265,10 @@ pub use keycodes::chrome::{
 #[cfg(target_os = "macos")]
 pub use crate::keycodes::macos::{code_from_key, key_from_code, virtual_keycodes::*};
 #[cfg(target_os = "macos")]
-use crate::macos::{display_size as _display_size, listen as _listen, simulate as _simulate};
+use crate::macos::{
+    display_size as _display_size, listen as _listen, pressed_keys as _pressed_keys,
+    simulate as _simulate,
+};
 #[cfg(target_os = "macos")]
 pub use crate::macos::{set_is_main_thread, Keyboard, VirtualInput};
 #[cfg(target_os = "macos")]
@@ -268,7 +277,10 @@ pub use core_graphics::{event::CGEventTapLocation, event_source::CGEventSourceSt
 #[cfg(any(target_os = "android", target_os = "linux"))]
 pub use crate::keycodes::linux::{code_from_key, key_from_code};
 #[cfg(target_os = "linux")]
-use crate::linux::{display_size as _display_size, listen as _listen, simulate as _simulate};
+use crate::linux::{
+    display_size as _display_size, listen as _listen, pressed_keys as _pressed_keys,
+    simulate as _simulate,
+};
 #[cfg(target_os = "linux")]
 pub use crate::linux::{simulate_char, simulate_unicode, Keyboard};
 
@@ -276,9 +288,9 @@ pub use crate::linux::{simulate_char, simulate_unicode, Keyboard};
 pub use crate::keycodes::windows::key_from_scancode;
 #[cfg(target_os = "windows")]
 pub use crate::windows::{
-    display_size as _display_size, get_modifier, listen as _listen, set_modifier,
-    simulate as _simulate, simulate_char, simulate_code, simulate_key_unicode, simulate_unicode,
-    simulate_unistr, vk_to_scancode, Keyboard,
+    display_size as _display_size, get_modifier, listen as _listen, pressed_keys as _pressed_keys,
+    set_modifier, simulate as _simulate, simulate_char, simulate_code, simulate_key_unicode,
+    simulate_unicode, simulate_unistr, vk_to_scancode, Keyboard,
 };
 
 pub use crate::rdev::UnicodeInfo;
@@ -341,6 +353,8 @@ where
 ///     send(&EventType::Wheel {
 ///         delta_x: 0,
 ///         delta_y: 1,
+///         precise_delta_x: 0.0,
+///         precise_delta_y: 1.0,
 ///     });
 /// }
 /// ```
@@ -363,6 +377,45 @@ pub fn display_size() -> Result<(u64, u64), DisplayError> {
     _display_size()
 }
 
+/// Returns the keys the OS currently considers held down, independent of any
+/// events received. Useful to recover from releases that were never delivered,
+/// e.g. while the screen was locked.
+/// On Linux this asks the X server through `XQueryKeymap`.
+///
+/// ```no_run
+/// use rdev::pressed_keys;
+///
+/// let keys = pressed_keys().unwrap();
+/// println!("Held down: {:?}", keys);
+/// ```
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+pub fn pressed_keys() -> Result<Vec<Key>, DisplayError> {
+    _pressed_keys()
+}
+
+/// Whether the X server supports the named extension, e.g. "RECORD" which
+/// `listen` relies on. Useful to explain why listening fails.
+///
+/// ```no_run
+/// use rdev::has_extension;
+///
+/// println!("XRecord available: {}", has_extension("RECORD").unwrap());
+/// ```
+#[cfg(target_os = "linux")]
+pub use crate::linux::has_extension;
+
+/// Keeps one X connection open to query the focused window, X errors on it
+/// never reach the process wide error handler.
+///
+/// ```no_run
+/// use rdev::ActiveWindow;
+///
+/// let active_window = ActiveWindow::new().unwrap();
+/// println!("Typing into: {:?}", active_window.class());
+/// ```
+#[cfg(target_os = "linux")]
+pub use crate::linux::ActiveWindow;
+
 #[cfg(target_os = "linux")]
 pub use crate::linux::{
     disable_grab, enable_grab, exit_grab_listen, is_grabbed, start_grab_listen,
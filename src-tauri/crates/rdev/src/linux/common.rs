@@ -1,9 +1,11 @@
 use crate::linux::keyboard::Keyboard;
 use crate::keycodes::linux::key_from_code;
-use crate::rdev::{Button, Event, EventType, KeyboardState};
+use crate::rdev::{Button, Event, EventType, Key, KeyboardState};
+use std::cell::Cell;
 use std::convert::TryInto;
-use std::os::raw::{c_int, c_uchar, c_uint};
-use std::ptr::null;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int, c_uchar, c_uint};
+use std::ptr::{null, null_mut};
 use std::time::SystemTime;
 use x11::xlib;
 
@@ -13,6 +15,11 @@ pub const FALSE: c_int = 0;
 // A global for the callbacks.
 pub static mut KEYBOARD: Option<Keyboard> = None;
 
+thread_local! {
+    // set by `trap_x_error`, the connection trapping errors stays on one thread
+    static X_ERROR: Cell<bool> = const { Cell::new(false) };
+}
+
 pub fn convert_event(code: c_uchar, type_: c_int, x: f64, y: f64) -> Option<EventType> {
     match type_ {
         xlib::KeyPress => {
@@ -23,20 +30,16 @@ pub fn convert_event(code: c_uchar, type_: c_int, x: f64, y: f64) -> Option<Even
             let key = key_from_code(code.into());
             Some(EventType::KeyRelease(key))
         }
-        // Xlib does not implement wheel events left and right afaik.
-        // But MacOS does, so we need to acknowledge the larger event space.
+        // Xlib reports each wheel notch as a press of buttons 4 (up), 5 (down),
+        // 6 (left) and 7 (right), followed by a matching release we ignore.
         xlib::ButtonPress => match code {
             1 => Some(EventType::ButtonPress(Button::Left)),
             2 => Some(EventType::ButtonPress(Button::Middle)),
             3 => Some(EventType::ButtonPress(Button::Right)),
-            4 => Some(EventType::Wheel {
-                delta_y: 1,
-                delta_x: 0,
-            }),
-            5 => Some(EventType::Wheel {
-                delta_y: -1,
-                delta_x: 0,
-            }),
+            4 => Some(wheel_notch(0, 1)),
+            5 => Some(wheel_notch(0, -1)),
+            6 => Some(wheel_notch(-1, 0)),
+            7 => Some(wheel_notch(1, 0)),
             #[allow(clippy::useless_conversion)]
             code => Some(EventType::ButtonPress(Button::Unknown(code))),
         },
@@ -44,7 +47,7 @@ pub fn convert_event(code: c_uchar, type_: c_int, x: f64, y: f64) -> Option<Even
             1 => Some(EventType::ButtonRelease(Button::Left)),
             2 => Some(EventType::ButtonRelease(Button::Middle)),
             3 => Some(EventType::ButtonRelease(Button::Right)),
-            4 | 5 => None,
+            4..=7 => None,
             #[allow(clippy::useless_conversion)]
             _ => Some(EventType::ButtonRelease(Button::Unknown(code))),
         },
@@ -53,6 +56,15 @@ pub fn convert_event(code: c_uchar, type_: c_int, x: f64, y: f64) -> Option<Even
     }
 }
 
+fn wheel_notch(delta_x: i64, delta_y: i64) -> EventType {
+    EventType::Wheel {
+        delta_x,
+        delta_y,
+        precise_delta_x: delta_x as f64,
+        precise_delta_y: delta_y as f64,
+    }
+}
+
 pub fn convert(
     keyboard: &mut Option<Keyboard>,
     code: c_uint,
@@ -63,11 +75,17 @@ pub fn convert(
     let event_type = convert_event(code as c_uchar, type_, x, y)?;
     let kb: &mut Keyboard = (*keyboard).as_mut()?;
     let unicode = kb.add(&event_type);
+    // keys report their keysym, as when grabbing, the keycode is kept in
+    // position_code
+    let platform_code = match event_type {
+        EventType::KeyPress(_) | EventType::KeyRelease(_) => kb.keysym(),
+        _ => code,
+    };
     Some(Event {
         event_type,
         time: SystemTime::now(),
         unicode,
-        platform_code: code as _,
+        platform_code,
         position_code: code as _,
         usb_hid: 0,
     })
@@ -88,6 +106,29 @@ impl Display {
         }
     }
 
+    /// Keeps X errors on this connection away from the process wide handler,
+    /// which GTK owns and which may exit on them. Only the thread using the
+    /// connection sees them, through `take_error`.
+    pub fn trap_errors(&self) -> bool {
+        unsafe {
+            let codes = xlib::XAddExtension(self.display);
+            if codes.is_null() {
+                return false;
+            }
+            xlib::XESetError(self.display, (*codes).extension, Some(trap_x_error));
+            true
+        }
+    }
+
+    /// Whether a request failed since the last call, waiting for the server
+    /// to answer everything sent so far.
+    fn take_error(&self) -> bool {
+        unsafe {
+            xlib::XSync(self.display, FALSE);
+        }
+        X_ERROR.with(|error| error.replace(false))
+    }
+
     pub fn get_size(&self) -> Option<(u64, u64)> {
         unsafe {
             let screen_ptr = xlib::XDefaultScreenOfDisplay(self.display);
@@ -102,6 +143,107 @@ impl Display {
         }
     }
 
+    /// Keys the X server currently considers held down, via `XQueryKeymap`.
+    pub fn get_pressed_keys(&self) -> Option<Vec<Key>> {
+        let mut keymap: [c_char; 32] = [0; 32];
+        unsafe {
+            if xlib::XQueryKeymap(self.display, keymap.as_mut_ptr()) == 0 {
+                return None;
+            }
+        }
+        let mut keys = Vec::new();
+        for (byte_index, byte) in keymap.iter().enumerate() {
+            for bit in 0..8 {
+                if (*byte as u8) & (1 << bit) != 0 {
+                    let code = (byte_index * 8 + bit) as u32;
+                    keys.push(key_from_code(code));
+                }
+            }
+        }
+        Some(keys)
+    }
+
+    /// Whether the X server supports the named extension, e.g. "RECORD".
+    pub fn has_extension(&self, name: &str) -> bool {
+        let name = match CString::new(name) {
+            Ok(name) => name,
+            Err(_) => return false,
+        };
+        let (mut opcode, mut event, mut error) = (0, 0, 0);
+        unsafe {
+            xlib::XQueryExtension(
+                self.display,
+                name.as_ptr(),
+                &mut opcode,
+                &mut event,
+                &mut error,
+            ) != 0
+        }
+    }
+
+    /// `WM_CLASS` of the window the window manager reports as
+    /// `_NET_ACTIVE_WINDOW`, e.g. "firefox". The active window may be gone by
+    /// the time we ask about it, call `trap_errors` first.
+    pub fn get_active_window_class(&self) -> Option<String> {
+        let atom_name = CString::new("_NET_ACTIVE_WINDOW").ok()?;
+        self.take_error();
+        unsafe {
+            let atom = xlib::XInternAtom(self.display, atom_name.as_ptr(), TRUE);
+            if atom == 0 {
+                return None;
+            }
+
+            let (mut actual_type, mut actual_format) = (0, 0);
+            let (mut items, mut bytes_after) = (0, 0);
+            let mut property: *mut c_uchar = null_mut();
+            let status = xlib::XGetWindowProperty(
+                self.display,
+                xlib::XDefaultRootWindow(self.display),
+                atom,
+                0,
+                1,
+                FALSE,
+                xlib::XA_WINDOW,
+                &mut actual_type,
+                &mut actual_format,
+                &mut items,
+                &mut bytes_after,
+                &mut property,
+            );
+            let mut window = 0;
+            if !property.is_null() {
+                if status == 0 && items > 0 && actual_format == 32 {
+                    window = *(property as *const xlib::Window);
+                }
+                xlib::XFree(property as *mut _);
+            }
+
+            let mut class = None;
+            let mut hint = xlib::XClassHint {
+                res_name: null_mut(),
+                res_class: null_mut(),
+            };
+            if window != 0 && xlib::XGetClassHint(self.display, window, &mut hint) != 0 {
+                if !hint.res_class.is_null() {
+                    class = Some(
+                        CStr::from_ptr(hint.res_class)
+                            .to_string_lossy()
+                            .into_owned(),
+                    );
+                    xlib::XFree(hint.res_class as *mut _);
+                }
+                if !hint.res_name.is_null() {
+                    xlib::XFree(hint.res_name as *mut _);
+                }
+            }
+
+            if self.take_error() {
+                return None;
+            }
+            class
+        }
+    }
+
     #[allow(dead_code)]
     pub fn get_mouse_pos(&self) -> Option<(u64, u64)> {
         unsafe {
@@ -128,6 +270,19 @@ impl Display {
         }
     }
 }
+
+// per connection error hook, a non-zero return keeps Xlib from calling the
+// global handler
+unsafe extern "C" fn trap_x_error(
+    _display: *mut xlib::Display,
+    _error: *mut xlib::xError,
+    _codes: *mut xlib::XExtCodes,
+    _ret_code: *mut c_int,
+) -> c_int {
+    X_ERROR.with(|error| error.set(true));
+    TRUE
+}
+
 impl Drop for Display {
     fn drop(&mut self) {
         unsafe {
@@ -135,3 +290,21 @@ impl Drop for Display {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wheel_buttons() {
+        let wheel = |code| convert_event(code, xlib::ButtonPress, 0.0, 0.0);
+        assert_eq!(wheel(4), Some(wheel_notch(0, 1)));
+        assert_eq!(wheel(5), Some(wheel_notch(0, -1)));
+        assert_eq!(wheel(6), Some(wheel_notch(-1, 0)));
+        assert_eq!(wheel(7), Some(wheel_notch(1, 0)));
+        for code in 4..=7 {
+            assert_eq!(convert_event(code, xlib::ButtonRelease, 0.0, 0.0), None);
+        }
+        assert_eq!(wheel(8), Some(EventType::ButtonPress(Button::Unknown(8))));
+    }
+}
@@ -1,7 +1,39 @@
 use crate::linux::common::Display;
-use crate::rdev::DisplayError;
+use crate::rdev::{DisplayError, Key};
 
 pub fn display_size() -> Result<(u64, u64), DisplayError> {
     let display = Display::new().ok_or(DisplayError::NoDisplay)?;
     display.get_size().ok_or(DisplayError::NoDisplay)
 }
+
+pub fn pressed_keys() -> Result<Vec<Key>, DisplayError> {
+    let display = Display::new().ok_or(DisplayError::NoDisplay)?;
+    display.get_pressed_keys().ok_or(DisplayError::NoDisplay)
+}
+
+pub fn has_extension(name: &str) -> Result<bool, DisplayError> {
+    let display = Display::new().ok_or(DisplayError::NoDisplay)?;
+    Ok(display.has_extension(name))
+}
+
+/// Connection kept open to ask for the focused window again and again, to be
+/// used from the thread that opened it.
+pub struct ActiveWindow {
+    display: Display,
+}
+
+impl ActiveWindow {
+    pub fn new() -> Result<ActiveWindow, DisplayError> {
+        let display = Display::new().ok_or(DisplayError::NoDisplay)?;
+        if !display.trap_errors() {
+            return Err(DisplayError::NoDisplay);
+        }
+        Ok(ActiveWindow { display })
+    }
+
+    /// `WM_CLASS` of the focused window, `None` when the window manager
+    /// doesn't advertise one through `_NET_ACTIVE_WINDOW`.
+    pub fn class(&self) -> Option<String> {
+        self.display.get_active_window_class()
+    }
+}
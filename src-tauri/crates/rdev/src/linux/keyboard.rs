@@ -171,25 +171,7 @@ impl Keyboard {
         }
         const BUF_LEN: usize = 4;
         let mut buf = [0_u8; BUF_LEN];
-        let MyDisplay(display) = *self.display;
-        let mut key = xlib::XKeyEvent {
-            display,
-            root: 0,
-            window: *self.window,
-            subwindow: 0,
-            x: 0,
-            y: 0,
-            x_root: 0,
-            y_root: 0,
-            state,
-            keycode,
-            same_screen: 0,
-            send_event: 0,
-            serial: self.serial,
-            type_: xlib::KeyPress,
-            time: xlib::CurrentTime,
-        };
-        self.serial += 1;
+        let mut key = self.key_event(keycode, state);
 
         let mut event = xlib::XEvent { key };
 
@@ -246,6 +228,36 @@ impl Keyboard {
         })
     }
 
+    /// Looks up the keysym only, leaving the input method and its dead key
+    /// state alone.
+    pub(crate) unsafe fn keysym_from_code(&mut self, keycode: c_uint, state: c_uint) -> KeySym {
+        let mut key = self.key_event(keycode, state);
+        xlookup_string(&mut key)
+    }
+
+    fn key_event(&mut self, keycode: c_uint, state: c_uint) -> XKeyEvent {
+        let MyDisplay(display) = *self.display;
+        let key = xlib::XKeyEvent {
+            display,
+            root: 0,
+            window: *self.window,
+            subwindow: 0,
+            x: 0,
+            y: 0,
+            x_root: 0,
+            y_root: 0,
+            state,
+            keycode,
+            same_screen: 0,
+            send_event: 0,
+            serial: self.serial,
+            type_: xlib::KeyPress,
+            time: xlib::CurrentTime,
+        };
+        self.serial += 1;
+        key
+    }
+
     pub fn is_dead(&mut self) -> bool {
         let ptr = unsafe { XKeysymToString(*self.keysym) };
         if ptr.is_null() {
@@ -272,7 +284,13 @@ impl KeyboardState for Keyboard {
                 let state = state & 0xFFFB;
                 unsafe { self.unicode_from_code(keycode, state) }
             }
-            EventType::KeyRelease(_key) => None,
+            EventType::KeyRelease(key) => {
+                // releases type nothing, but should report their keysym too
+                let keycode = code_from_key(*key)?;
+                let state = unsafe { self.get_current_modifiers().unwrap_or_default() };
+                *self.keysym = unsafe { self.keysym_from_code(keycode, state & 0xFFFB) };
+                None
+            }
             _ => None,
         }
     }
@@ -2,17 +2,25 @@ extern crate libc;
 extern crate x11;
 use crate::linux::common::{convert, FALSE, KEYBOARD};
 use crate::linux::keyboard::Keyboard;
+use crate::linux::scroll::ScrollListener;
 use crate::rdev::{Event, ListenError};
+use std::cell::Cell;
 use std::convert::TryInto;
 use std::ffi::CStr;
 use std::os::raw::{c_char, c_int, c_uchar, c_uint, c_ulong};
-use std::ptr::null;
+use std::ptr::{null, null_mut};
 use x11::xlib;
 use x11::xrecord;
 
 static mut RECORD_ALL_CLIENTS: c_ulong = xrecord::XRecordAllClients;
 static mut GLOBAL_CALLBACK: Option<Box<dyn FnMut(Event)>> = None;
 
+thread_local! {
+    // set while XInput2 reports the wheel, X makes the core wheel buttons
+    // from the same scrolling
+    static SMOOTH_SCROLL: Cell<bool> = const { Cell::new(false) };
+}
+
 pub fn listen<T>(callback: T) -> Result<(), ListenError>
 where
     T: FnMut(Event) + 'static,
@@ -59,14 +67,48 @@ where
         }
 
         xlib::XSync(dpy_control, FALSE);
-        // Run
-        let result =
-            xrecord::XRecordEnableContext(dpy_control, context, Some(record_callback), &mut 0);
+        let scroll = if crate::keyboard_only() {
+            None
+        } else {
+            ScrollListener::new()
+        };
+        let Some(mut scroll) = scroll else {
+            // Run
+            let result =
+                xrecord::XRecordEnableContext(dpy_control, context, Some(record_callback), &mut 0);
+            if result == 0 {
+                return Err(ListenError::RecordContextEnablingError);
+            }
+            return Ok(());
+        };
+
+        // both connections on this thread, the callback isn't shared
+        SMOOTH_SCROLL.with(|smooth| smooth.set(true));
+        let result = xrecord::XRecordEnableContextAsync(
+            dpy_control,
+            context,
+            Some(record_callback),
+            null_mut(),
+        );
         if result == 0 {
             return Err(ListenError::RecordContextEnablingError);
         }
+        let mut fds = [dpy_control, scroll.display()].map(|display| libc::pollfd {
+            fd: xlib::XConnectionNumber(display),
+            events: libc::POLLIN,
+            revents: 0,
+        });
+        loop {
+            xrecord::XRecordProcessReplies(dpy_control);
+            scroll.dispatch(&mut |event| {
+                if let Some(callback) = &mut GLOBAL_CALLBACK {
+                    callback(event);
+                }
+            });
+            // until either has more, an interrupted wait just goes around
+            libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, -1);
+        }
     }
-    Ok(())
 }
 
 // No idea how to do that properly relevant doc lives here:
@@ -115,7 +157,11 @@ unsafe extern "C" fn record_callback(
     let x = xdatum.root_x as f64;
     let y = xdatum.root_y as f64;
 
-    if let Some(event) = convert(&mut KEYBOARD, code, type_, x, y) {
+    let wheel_button =
+        matches!(type_, xlib::ButtonPress | xlib::ButtonRelease) && (4..=7).contains(&code);
+    if wheel_button && SMOOTH_SCROLL.with(Cell::get) {
+        // the scroll listener has it
+    } else if let Some(event) = convert(&mut KEYBOARD, code, type_, x, y) {
         if let Some(callback) = &mut GLOBAL_CALLBACK {
             callback(event);
         }
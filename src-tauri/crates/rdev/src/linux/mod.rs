@@ -6,9 +6,10 @@ mod display;
 mod grab;
 mod keyboard;
 mod listen;
+mod scroll;
 mod simulate;
 
-pub use crate::linux::display::display_size;
+pub use crate::linux::display::{display_size, has_extension, pressed_keys, ActiveWindow};
 pub use crate::linux::grab::{
     disable_grab, enable_grab, exit_grab_listen, is_grabbed, start_grab_listen,
 };
@@ -0,0 +1,375 @@
+extern crate libc;
+extern crate x11;
+use crate::rdev::{Event, EventType};
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::os::raw::{c_int, c_uchar};
+use std::ptr::null;
+use std::slice;
+use std::time::SystemTime;
+use x11::{xinput2, xlib};
+
+/// A scroll valuator, moving `increment` per wheel notch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ScrollAxis {
+    horizontal: bool,
+    increment: f64,
+    /// relative axes report deltas, absolute ones positions
+    relative: bool,
+}
+
+/// Fractional wheel notches from the XInput2 scroll valuators touchpads and
+/// high-resolution wheels report. X only hands core clients whole notches,
+/// as presses of buttons 4 to 7.
+#[derive(Default)]
+struct SmoothScroll {
+    /// by (source device, valuator number)
+    axes: HashMap<(c_int, c_int), ScrollAxis>,
+    /// last value seen on absolute axes
+    positions: HashMap<(c_int, c_int), f64>,
+    /// fractions of a notch not in `delta_x` and `delta_y` yet
+    pending: (f64, f64),
+}
+
+impl SmoothScroll {
+    /// Wheel event for valuators of `device` moving to `values`, given as
+    /// (valuator number, value). `None` when none of them scroll.
+    fn scroll(
+        &mut self,
+        device: c_int,
+        values: impl Iterator<Item = (c_int, f64)>,
+    ) -> Option<EventType> {
+        let (mut delta_x, mut delta_y) = (0.0, 0.0);
+        for (number, value) in values {
+            let Some(axis) = self.axes.get(&(device, number)) else {
+                continue;
+            };
+            let delta = if axis.relative {
+                value
+            } else {
+                match self.positions.insert((device, number), value) {
+                    Some(last) => value - last,
+                    None => continue,
+                }
+            };
+            // X counts down and right, rdev up and right
+            if axis.horizontal {
+                delta_x += delta / axis.increment;
+            } else {
+                delta_y -= delta / axis.increment;
+            }
+        }
+        if delta_x == 0.0 && delta_y == 0.0 {
+            return None;
+        }
+
+        self.pending.0 += delta_x;
+        self.pending.1 += delta_y;
+        let notches = (self.pending.0.trunc(), self.pending.1.trunc());
+        self.pending.0 -= notches.0;
+        self.pending.1 -= notches.1;
+        Some(EventType::Wheel {
+            delta_x: notches.0 as i64,
+            delta_y: notches.1 as i64,
+            precise_delta_x: delta_x,
+            precise_delta_y: delta_y,
+        })
+    }
+
+    /// Whether `device` scrolls that way through valuators, its wheel
+    /// buttons are then X emulating them.
+    fn has_axis(&self, device: c_int, horizontal: bool) -> bool {
+        self.axes
+            .iter()
+            .any(|((id, _), axis)| *id == device && axis.horizontal == horizontal)
+    }
+}
+
+/// XInput2 connection reading scroll valuators, the XRecord one only sees
+/// core events.
+pub struct ScrollListener {
+    display: *mut xlib::Display,
+    opcode: c_int,
+    scroll: SmoothScroll,
+}
+
+impl ScrollListener {
+    /// `None` without XInput 2.1, which brought smooth scrolling.
+    pub fn new() -> Option<ScrollListener> {
+        unsafe {
+            let display = xlib::XOpenDisplay(null());
+            if display.is_null() {
+                return None;
+            }
+            let mut listener = ScrollListener {
+                display,
+                opcode: 0,
+                scroll: SmoothScroll::default(),
+            };
+            let name = CString::new("XInputExtension").ok()?;
+            let (mut event, mut error) = (0, 0);
+            if xlib::XQueryExtension(
+                display,
+                name.as_ptr(),
+                &mut listener.opcode,
+                &mut event,
+                &mut error,
+            ) == 0
+            {
+                return None;
+            }
+            let (mut major, mut minor) = (2, 1);
+            if xinput2::XIQueryVersion(display, &mut major, &mut minor) != xlib::Success as c_int
+                || (major, minor) < (2, 1)
+            {
+                return None;
+            }
+
+            let root = xlib::XDefaultRootWindow(display);
+            // raw events only go to the root window, and aren't emulated
+            listener.select(
+                root,
+                xinput2::XIAllMasterDevices,
+                &[xinput2::XI_RawMotion, xinput2::XI_RawButtonPress],
+            );
+            // scroll valuators come and go with devices
+            listener.select(
+                root,
+                xinput2::XIAllDevices,
+                &[xinput2::XI_HierarchyChanged, xinput2::XI_DeviceChanged],
+            );
+            listener.load_axes();
+            Some(listener)
+        }
+    }
+
+    unsafe fn select(&self, window: xlib::Window, device: c_int, events: &[c_int]) {
+        let mut mask = [0 as c_uchar; (xinput2::XI_LASTEVENT as usize).div_ceil(8)];
+        for event in events {
+            xinput2::XISetMask(&mut mask, *event);
+        }
+        let mut event_mask = xinput2::XIEventMask {
+            deviceid: device,
+            mask_len: mask.len() as c_int,
+            mask: mask.as_mut_ptr(),
+        };
+        xinput2::XISelectEvents(self.display, window, &mut event_mask, 1);
+    }
+
+    /// Reads the scroll valuators of every device.
+    unsafe fn load_axes(&mut self) {
+        self.scroll.axes.clear();
+        self.scroll.positions.clear();
+        let mut count = 0;
+        let devices = xinput2::XIQueryDevice(self.display, xinput2::XIAllDevices, &mut count);
+        if devices.is_null() {
+            return;
+        }
+        for device in slice::from_raw_parts(devices, count.max(0) as usize) {
+            if device.classes.is_null() {
+                continue;
+            }
+            let classes = slice::from_raw_parts(device.classes, device.num_classes.max(0) as usize);
+            // the scroll class names a valuator, whose mode says how to read it
+            let relative = |number| {
+                classes.iter().any(|class| {
+                    (**class)._type == xinput2::XIValuatorClass && {
+                        let valuator = &*(*class as *const xinput2::XIValuatorClassInfo);
+                        valuator.number == number && valuator.mode == xinput2::XIModeRelative
+                    }
+                })
+            };
+            for class in classes {
+                if (**class)._type != xinput2::XIScrollClass {
+                    continue;
+                }
+                let scroll = &*(*class as *const xinput2::XIScrollClassInfo);
+                if scroll.increment == 0.0 {
+                    continue;
+                }
+                self.scroll.axes.insert(
+                    (device.deviceid, scroll.number),
+                    ScrollAxis {
+                        horizontal: scroll.scroll_type == xinput2::XIScrollTypeHorizontal,
+                        increment: scroll.increment,
+                        relative: relative(scroll.number),
+                    },
+                );
+            }
+        }
+        xinput2::XIFreeDeviceInfo(devices);
+    }
+
+    pub fn display(&self) -> *mut xlib::Display {
+        self.display
+    }
+
+    /// Hands `callback` the scroll events that arrived, without waiting.
+    pub fn dispatch(&mut self, callback: &mut dyn FnMut(Event)) {
+        unsafe {
+            while xlib::XPending(self.display) > 0 {
+                let mut event: xlib::XEvent = std::mem::zeroed();
+                xlib::XNextEvent(self.display, &mut event);
+                let mut cookie = event.generic_event_cookie;
+                if cookie.type_ != xlib::GenericEvent
+                    || cookie.extension != self.opcode
+                    || xlib::XGetEventData(self.display, &mut cookie) == 0
+                {
+                    continue;
+                }
+                let event_type = match cookie.evtype {
+                    xinput2::XI_RawMotion => {
+                        let raw = &*(cookie.data as *const xinput2::XIRawEvent);
+                        self.scroll
+                            .scroll(raw.sourceid, raw_values(raw).into_iter())
+                    }
+                    xinput2::XI_RawButtonPress => {
+                        let raw = &*(cookie.data as *const xinput2::XIRawEvent);
+                        self.wheel_button(raw)
+                    }
+                    _ => {
+                        self.load_axes();
+                        None
+                    }
+                };
+                xlib::XFreeEventData(self.display, &mut cookie);
+                if let Some(event_type) = event_type {
+                    callback(Event {
+                        event_type,
+                        time: SystemTime::now(),
+                        unicode: None,
+                        platform_code: 0,
+                        position_code: 0,
+                        usb_hid: 0,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Wheel buttons of devices without scroll valuators, the core ones are
+    /// dropped while this listens.
+    fn wheel_button(&self, raw: &xinput2::XIRawEvent) -> Option<EventType> {
+        if raw.flags & xinput2::XIPointerEmulated != 0 {
+            return None;
+        }
+        let (delta_x, delta_y) = match raw.detail {
+            4 => (0, 1),
+            5 => (0, -1),
+            6 => (-1, 0),
+            7 => (1, 0),
+            _ => return None,
+        };
+        if self.scroll.has_axis(raw.sourceid, delta_x != 0) {
+            return None;
+        }
+        Some(EventType::Wheel {
+            delta_x,
+            delta_y,
+            precise_delta_x: delta_x as f64,
+            precise_delta_y: delta_y as f64,
+        })
+    }
+}
+
+impl Drop for ScrollListener {
+    fn drop(&mut self) {
+        unsafe {
+            xlib::XCloseDisplay(self.display);
+        }
+    }
+}
+
+/// (valuator number, value) for every valuator set in `raw`.
+unsafe fn raw_values(raw: &xinput2::XIRawEvent) -> Vec<(c_int, f64)> {
+    if raw.valuators.mask.is_null() {
+        return Vec::new();
+    }
+    let mask = slice::from_raw_parts(raw.valuators.mask, raw.valuators.mask_len.max(0) as usize);
+    // values are packed, one for each bit set
+    (0..mask.len() as c_int * 8)
+        .filter(|number| xinput2::XIMaskIsSet(mask, *number))
+        .enumerate()
+        .map(|(index, number)| (number, *raw.raw_values.add(index)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn touchpad() -> SmoothScroll {
+        let mut scroll = SmoothScroll::default();
+        for (number, horizontal) in [(2, true), (3, false)] {
+            scroll.axes.insert(
+                (11, number),
+                ScrollAxis {
+                    horizontal,
+                    increment: 15.0,
+                    relative: true,
+                },
+            );
+        }
+        scroll
+    }
+
+    fn wheel(delta_x: i64, delta_y: i64, precise_delta_x: f64, precise_delta_y: f64) -> EventType {
+        EventType::Wheel {
+            delta_x,
+            delta_y,
+            precise_delta_x,
+            precise_delta_y,
+        }
+    }
+
+    #[test]
+    fn test_smooth_scroll() {
+        let mut scroll = touchpad();
+        // pointer motion only
+        assert_eq!(
+            scroll.scroll(11, vec![(0, 4.0), (1, -2.0)].into_iter()),
+            None
+        );
+        // another device's valuators
+        assert_eq!(scroll.scroll(12, vec![(3, 15.0)].into_iter()), None);
+
+        // a third of a notch down, twice, then past the notch
+        assert_eq!(
+            scroll.scroll(11, vec![(3, 5.0)].into_iter()),
+            Some(wheel(0, 0, 0.0, -1.0 / 3.0))
+        );
+        assert_eq!(
+            scroll.scroll(11, vec![(3, 5.0)].into_iter()),
+            Some(wheel(0, 0, 0.0, -1.0 / 3.0))
+        );
+        assert_eq!(
+            scroll.scroll(11, vec![(0, 1.0), (3, 7.5)].into_iter()),
+            Some(wheel(0, -1, 0.0, -0.5))
+        );
+        assert_eq!(
+            scroll.scroll(11, vec![(2, 30.0)].into_iter()),
+            Some(wheel(2, 0, 2.0, 0.0))
+        );
+        assert!(scroll.has_axis(11, false));
+        assert!(!scroll.has_axis(12, false));
+    }
+
+    #[test]
+    fn test_absolute_scroll_axis() {
+        let mut scroll = SmoothScroll::default();
+        scroll.axes.insert(
+            (11, 3),
+            ScrollAxis {
+                horizontal: false,
+                increment: 120.0,
+                relative: false,
+            },
+        );
+        // the first position is only a starting point
+        assert_eq!(scroll.scroll(11, vec![(3, 1200.0)].into_iter()), None);
+        assert_eq!(
+            scroll.scroll(11, vec![(3, 1080.0)].into_iter()),
+            Some(wheel(0, 1, 0.0, 1.0))
+        );
+    }
+}
@@ -70,8 +70,16 @@ unsafe fn send_native(event_type: &EventType, display: *mut xlib::Display) -> Op
             xtest::XTestFakeMotionEvent(display, 0, x, y, 0)
             //     xlib::XWarpPointer(display, 0, root, 0, 0, 0, 0, *x as i32, *y as i32);
         }
-        EventType::Wheel { delta_y, .. } => {
-            let code = if *delta_y > 0 { 4 } else { 5 };
+        EventType::Wheel {
+            delta_x, delta_y, ..
+        } => {
+            let code = match (*delta_x, *delta_y) {
+                (_, y) if y > 0 => 4,
+                (_, y) if y < 0 => 5,
+                (x, _) if x < 0 => 6,
+                (x, _) if x > 0 => 7,
+                _ => return Some(()),
+            };
             xtest::XTestFakeButtonEvent(display, code, TRUE, 0)
                 & xtest::XTestFakeButtonEvent(display, code, FALSE, 0)
         }
@@ -216,11 +216,21 @@ pub unsafe fn convert(
             }
         }
         CGEventType::ScrollWheel => {
+            // line deltas, which are wheel notches, rather than pixels
             let delta_y =
-                cg_event.get_integer_value_field(EventField::SCROLL_WHEEL_EVENT_POINT_DELTA_AXIS_1);
+                cg_event.get_integer_value_field(EventField::SCROLL_WHEEL_EVENT_DELTA_AXIS_1);
             let delta_x =
-                cg_event.get_integer_value_field(EventField::SCROLL_WHEEL_EVENT_POINT_DELTA_AXIS_2);
-            Some(EventType::Wheel { delta_x, delta_y })
+                cg_event.get_integer_value_field(EventField::SCROLL_WHEEL_EVENT_DELTA_AXIS_2);
+            let precise_delta_y = cg_event
+                .get_double_value_field(EventField::SCROLL_WHEEL_EVENT_FIXED_POINT_DELTA_AXIS_1);
+            let precise_delta_x = cg_event
+                .get_double_value_field(EventField::SCROLL_WHEEL_EVENT_FIXED_POINT_DELTA_AXIS_2);
+            Some(EventType::Wheel {
+                delta_x,
+                delta_y,
+                precise_delta_x,
+                precise_delta_y,
+            })
         }
         _ => None,
     };
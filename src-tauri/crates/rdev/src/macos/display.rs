@@ -1,7 +1,29 @@
-use crate::rdev::DisplayError;
+use crate::keycodes::macos::code_from_key;
+use crate::rdev::{DisplayError, Key};
 use core_graphics::display::CGDisplay;
+use core_graphics::event::CGKeyCode;
+use core_graphics::event_source::CGEventSourceStateID;
+use strum::IntoEnumIterator;
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGEventSourceKeyState(state_id: CGEventSourceStateID, key: CGKeyCode) -> bool;
+}
 
 pub fn display_size() -> Result<(u64, u64), DisplayError> {
     let main = CGDisplay::main();
     Ok((main.pixels_wide(), main.pixels_high()))
 }
+
+pub fn pressed_keys() -> Result<Vec<Key>, DisplayError> {
+    let keys = Key::iter()
+        .filter(|key| !matches!(key, Key::Unknown(_) | Key::RawKey(_)))
+        .filter(|key| match code_from_key(*key) {
+            Some(code) => unsafe {
+                CGEventSourceKeyState(CGEventSourceStateID::HIDSystemState, code)
+            },
+            None => false,
+        })
+        .collect();
+    Ok(keys)
+}
@@ -138,11 +138,13 @@ unsafe fn convert_native_with_source(
             CGEvent::new_mouse_event(source, CGEventType::MouseMoved, point, CGMouseButton::Left)
                 .ok()
         }
-        EventType::Wheel { delta_x, delta_y } => {
+        EventType::Wheel {
+            delta_x, delta_y, ..
+        } => {
             let wheel_count = 2;
             CGEvent::new_scroll_event(
                 source,
-                ScrollEventUnit::PIXEL,
+                ScrollEventUnit::LINE,
                 wheel_count,
                 (*delta_y).try_into().ok()?,
                 (*delta_x).try_into().ok()?,
@@ -317,12 +317,22 @@ pub enum EventType {
     },
     /// `delta_y` represents vertical scroll and `delta_x` represents horizontal scroll.
     /// Positive values correspond to scrolling up or right and negative values
-    /// correspond to scrolling down or left
-    /// Note: Linux does not support horizontal scroll. When simulating scroll on Linux,
-    /// only the sign of delta_y is considered, and not the magnitude to determine wheelup or wheeldown.
+    /// correspond to scrolling down or left.
+    /// All deltas are in wheel notches on every platform: one click of a regular
+    /// wheel is 1 (a line on MacOS, `WHEEL_DELTA` on Windows). `delta_x` and `delta_y`
+    /// are whole notches, `precise_delta_x` and `precise_delta_y` carry the same scroll
+    /// in fractional notches so high-resolution wheels and trackpads that report less
+    /// than a notch per event are not rounded away.
+    /// Note: On Linux the precise deltas come from XInput 2.1 smooth scrolling, a
+    /// scroll smaller than a notch then has `delta_x` and `delta_y` at 0 until the
+    /// fractions add up. Without it, X11 only reports whole notches. When simulating
+    /// scroll on Linux, only the signs of delta_y and delta_x are considered, and not
+    /// the magnitude, to pick the wheel button.
     Wheel {
         delta_x: i64,
         delta_y: i64,
+        precise_delta_x: f64,
+        precise_delta_y: f64,
     },
 }
 
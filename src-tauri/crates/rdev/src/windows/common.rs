@@ -117,6 +117,8 @@ pub unsafe fn convert(param: WPARAM, lpdata: LPARAM) -> (Option<EventType>, u16)
                 Some(EventType::Wheel {
                     delta_x: 0,
                     delta_y: (delta / WHEEL_DELTA) as i64,
+                    precise_delta_x: 0.0,
+                    precise_delta_y: delta as f64 / WHEEL_DELTA as f64,
                 })
             }
             Ok(WM_MOUSEHWHEEL) => {
@@ -124,6 +126,8 @@ pub unsafe fn convert(param: WPARAM, lpdata: LPARAM) -> (Option<EventType>, u16)
                 Some(EventType::Wheel {
                     delta_x: (delta / WHEEL_DELTA) as i64,
                     delta_y: 0,
+                    precise_delta_x: delta as f64 / WHEEL_DELTA as f64,
+                    precise_delta_y: 0.0,
                 })
             }
             _ => None,
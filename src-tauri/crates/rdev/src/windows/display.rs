@@ -1,6 +1,8 @@
-use crate::rdev::DisplayError;
+use crate::keycodes::windows::code_from_key;
+use crate::rdev::{DisplayError, Key};
 use std::convert::TryInto;
-use winapi::um::winuser::{GetSystemMetrics, SM_CXSCREEN, SM_CYSCREEN};
+use strum::IntoEnumIterator;
+use winapi::um::winuser::{GetAsyncKeyState, GetSystemMetrics, SM_CXSCREEN, SM_CYSCREEN};
 
 pub fn display_size() -> Result<(u64, u64), DisplayError> {
     let w = unsafe {
@@ -15,3 +17,15 @@ pub fn display_size() -> Result<(u64, u64), DisplayError> {
     };
     Ok((w, h))
 }
+
+pub fn pressed_keys() -> Result<Vec<Key>, DisplayError> {
+    let keys = Key::iter()
+        .filter(|key| !matches!(key, Key::Unknown(_) | Key::RawKey(_)))
+        .filter(|key| match code_from_key(*key) {
+            // most significant bit is set while the key is down
+            Some(code) => unsafe { GetAsyncKeyState(code as _) as u16 & 0x8000 != 0 },
+            None => false,
+        })
+        .collect();
+    Ok(keys)
+}
@@ -9,7 +9,7 @@ mod simulate;
 
 
 pub use crate::windows::common::*;
-pub use crate::windows::display::display_size;
+pub use crate::windows::display::{display_size, pressed_keys};
 pub use crate::windows::grab::{exit_grab, grab, is_grabbed, set_event_popup, set_get_key_unicode};
 pub use crate::windows::keyboard::Keyboard;
 pub use crate::windows::listen::listen;
@@ -155,7 +155,9 @@ pub fn simulate(event_type: &EventType) -> Result<(), SimulateError> {
             Button::Right => sim_mouse_event(MOUSEEVENTF_RIGHTUP, 0, 0, 0),
             Button::Unknown(code) => sim_mouse_event(MOUSEEVENTF_XUP, 0, 0, (*code).into()),
         },
-        EventType::Wheel { delta_x, delta_y } => {
+        EventType::Wheel {
+            delta_x, delta_y, ..
+        } => {
             if *delta_x != 0 {
                 sim_mouse_event(
                     MOUSEEVENTF_HWHEEL,
@@ -0,0 +1,384 @@
+use std::{
+    sync::Mutex,
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::Deserialize;
+use tauri::{menu::MenuItem, AppHandle, Manager, Wry};
+
+use crate::app::state::AppState;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// When to visualize on its own, keyviz stays paused otherwise. Any matching
+/// condition is enough.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ActivationRules {
+    pub enabled: bool,
+    /// running processes, e.g. "obs" or "ffmpeg x11grab": the executable,
+    /// then words its arguments must contain
+    pub processes: Vec<String>,
+    /// foreground apps, e.g. "Code"
+    pub apps: Vec<String>,
+    /// local time of day as "HH:MM", `to` before `from` wraps past midnight
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+impl ActivationRules {
+    fn schedule(&self) -> Option<(u32, u32)> {
+        Some((
+            parse_time(self.from.as_deref()?)?,
+            parse_time(self.to.as_deref()?)?,
+        ))
+    }
+
+    pub fn has_conditions(&self) -> bool {
+        !self.processes.is_empty() || !self.apps.is_empty() || self.schedule().is_some()
+    }
+
+    /// Whether visualization should be on, `minute` being the local minute of
+    /// the day and `commands` the running processes.
+    pub fn evaluate(&self, minute: u32, app: Option<&str>, commands: &[Vec<String>]) -> bool {
+        let scheduled = self
+            .schedule()
+            .is_some_and(|(from, to)| in_window(minute, from, to));
+        let focused = app.is_some_and(|app| {
+            self.apps
+                .iter()
+                .any(|wanted| wanted.eq_ignore_ascii_case(app))
+        });
+        let recording = self.processes.iter().any(|pattern| {
+            commands
+                .iter()
+                .any(|command| matches_process(pattern, command))
+        });
+        scheduled || focused || recording
+    }
+}
+
+/// "09:30" to minutes since midnight.
+fn parse_time(time: &str) -> Option<u32> {
+    let (hours, minutes) = time.trim().split_once(':')?;
+    let (hours, minutes): (u32, u32) = (hours.parse().ok()?, minutes.parse().ok()?);
+    (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+}
+
+fn in_window(minute: u32, from: u32, to: u32) -> bool {
+    if from <= to {
+        (from..to).contains(&minute)
+    } else {
+        minute >= from || minute < to
+    }
+}
+
+/// Whether `pattern` names the executable of `command` and every other word
+/// of it shows up in the arguments.
+fn matches_process(pattern: &str, command: &[String]) -> bool {
+    let mut words = pattern.split_whitespace();
+    let (Some(executable), Some(program)) = (words.next(), command.first()) else {
+        return false;
+    };
+    let program = program.rsplit(['/', '\\']).next().unwrap_or(program);
+    let program = program.strip_suffix(".exe").unwrap_or(program);
+    if !program.eq_ignore_ascii_case(executable) {
+        return false;
+    }
+    let args = command[1..].join(" ").to_lowercase();
+    words.all(|word| args.contains(&word.to_lowercase()))
+}
+
+/// Command lines of the running processes.
+#[cfg(target_os = "linux")]
+pub fn running_processes() -> Vec<Vec<String>> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return vec![];
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_name().to_string_lossy().parse::<u32>().is_ok())
+        .filter_map(|entry| std::fs::read(entry.path().join("cmdline")).ok())
+        .filter(|cmdline| !cmdline.is_empty())
+        .map(|cmdline| {
+            cmdline
+                .split(|byte| *byte == 0)
+                .filter(|arg| !arg.is_empty())
+                .map(|arg| String::from_utf8_lossy(arg).into_owned())
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+pub fn running_processes() -> Vec<Vec<String>> {
+    use std::{mem, ptr};
+
+    let mut argmax: libc::c_int = 0;
+    let mut size = mem::size_of::<libc::c_int>();
+    let mut mib = [libc::CTL_KERN, libc::KERN_ARGMAX];
+    let status = unsafe {
+        libc::sysctl(
+            mib.as_mut_ptr(),
+            2,
+            &mut argmax as *mut _ as *mut libc::c_void,
+            &mut size,
+            ptr::null_mut(),
+            0,
+        )
+    };
+    if status != 0 || argmax <= 0 {
+        return vec![];
+    }
+
+    let count = unsafe { libc::proc_listallpids(ptr::null_mut(), 0) };
+    if count <= 0 {
+        return vec![];
+    }
+    // room for processes started in between
+    let mut pids: Vec<libc::pid_t> = vec![0; count as usize + 32];
+    let count = unsafe {
+        libc::proc_listallpids(
+            pids.as_mut_ptr() as *mut libc::c_void,
+            (pids.len() * mem::size_of::<libc::pid_t>()) as libc::c_int,
+        )
+    };
+    pids.truncate(count.max(0) as usize);
+
+    let mut buffer = vec![0u8; argmax as usize];
+    pids.into_iter()
+        .filter_map(|pid| {
+            let mut mib = [libc::CTL_KERN, libc::KERN_PROCARGS2, pid];
+            let mut size = buffer.len();
+            let status = unsafe {
+                libc::sysctl(
+                    mib.as_mut_ptr(),
+                    3,
+                    buffer.as_mut_ptr() as *mut libc::c_void,
+                    &mut size,
+                    ptr::null_mut(),
+                    0,
+                )
+            };
+            // other users' processes are off limits
+            (status == 0).then(|| parse_procargs(&buffer[..size]))
+        })
+        .filter(|command| !command.is_empty())
+        .collect()
+}
+
+/// Arguments out of `KERN_PROCARGS2`: the argument count, the executable
+/// path, padding, then the arguments, all NUL terminated.
+#[cfg(target_os = "macos")]
+fn parse_procargs(buffer: &[u8]) -> Vec<String> {
+    let Some((argc, rest)) = buffer.split_first_chunk::<4>() else {
+        return vec![];
+    };
+    let argc = i32::from_ne_bytes(*argc).max(0) as usize;
+    let Some(path_end) = rest.iter().position(|byte| *byte == 0) else {
+        return vec![];
+    };
+    rest[path_end..]
+        .split(|byte| *byte == 0)
+        .filter(|arg| !arg.is_empty())
+        .take(argc)
+        .map(|arg| String::from_utf8_lossy(arg).into_owned())
+        .collect()
+}
+
+/// Only image names on Windows, arguments aren't listed.
+#[cfg(target_os = "windows")]
+pub fn running_processes() -> Vec<Vec<String>> {
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W,
+        TH32CS_SNAPPROCESS,
+    };
+
+    let Ok(snapshot) = (unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) }) else {
+        return vec![];
+    };
+    let mut entry = PROCESSENTRY32W {
+        dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+        ..Default::default()
+    };
+    let mut processes = vec![];
+    let mut found = unsafe { Process32FirstW(snapshot, &mut entry) }.as_bool();
+    while found {
+        // "obs64.exe"
+        let len = entry
+            .szExeFile
+            .iter()
+            .position(|c| *c == 0)
+            .unwrap_or(entry.szExeFile.len());
+        processes.push(vec![String::from_utf16_lossy(&entry.szExeFile[..len])]);
+        found = unsafe { Process32NextW(snapshot, &mut entry) }.as_bool();
+    }
+    unsafe { CloseHandle(snapshot) };
+    processes
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+pub fn running_processes() -> Vec<Vec<String>> {
+    vec![]
+}
+
+/// Minutes since local midnight.
+pub fn local_minute_of_day(now: SystemTime) -> u32 {
+    let seconds = now
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    let local = seconds + utc_offset_seconds(seconds);
+    (local.rem_euclid(86_400) / 60) as u32
+}
+
+#[cfg(unix)]
+// time_t and c_long are narrower on 32 bit targets
+#[allow(clippy::unnecessary_cast)]
+fn utc_offset_seconds(seconds: i64) -> i64 {
+    let time = seconds as libc::time_t;
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    let result = unsafe { libc::localtime_r(&time, &mut tm) };
+    if result.is_null() {
+        0
+    } else {
+        tm.tm_gmtoff as i64
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn utc_offset_seconds(_seconds: i64) -> i64 {
+    use windows::Win32::System::Time::{GetTimeZoneInformation, TIME_ZONE_INFORMATION};
+    const TIME_ZONE_ID_DAYLIGHT: u32 = 2;
+
+    let mut info = TIME_ZONE_INFORMATION::default();
+    let id = unsafe { GetTimeZoneInformation(&mut info) };
+    // local = UTC - bias, in minutes
+    let mut bias = info.Bias;
+    if id == TIME_ZONE_ID_DAYLIGHT {
+        bias += info.DaylightBias;
+    }
+    -i64::from(bias) * 60
+}
+
+#[cfg(not(any(unix, target_os = "windows")))]
+fn utc_offset_seconds(_seconds: i64) -> i64 {
+    0
+}
+
+/// Turns visualization on and off as the rules start and stop matching.
+/// Only changes are acted on, so stopping or starting by hand sticks until
+/// the next one.
+pub fn start_activation_watcher(app_handle: AppHandle, toggle_item: MenuItem<Wry>) {
+    thread::spawn(move || {
+        let mut last = None;
+        loop {
+            thread::sleep(POLL_INTERVAL);
+
+            let state = app_handle.state::<Mutex<AppState>>();
+            let (rules, app) = {
+                let app_state = state.lock().unwrap();
+                if !app_state.activation.enabled || !app_state.activation.has_conditions() {
+                    last = None;
+                    continue;
+                }
+                (
+                    app_state.activation.clone(),
+                    app_state.foreground_app.clone(),
+                )
+            };
+            // outside the lock, listing processes takes a moment
+            let commands = if rules.processes.is_empty() {
+                vec![]
+            } else {
+                running_processes()
+            };
+            let wanted = rules.evaluate(
+                local_minute_of_day(SystemTime::now()),
+                app.as_deref(),
+                &commands,
+            );
+            if last == Some(wanted) {
+                continue;
+            }
+
+            let mut app_state = state.lock().unwrap();
+            // waking up from idle restores listening itself
+            if app_state.idle.idle {
+                continue;
+            }
+            last = Some(wanted);
+            if app_state.listening == wanted {
+                continue;
+            }
+            println!(
+                "⚡ Activation rules {}",
+                if wanted { "matched" } else { "no longer match" }
+            );
+            // only flips the state, the menu and tray follow on the main
+            // thread once it gets to them, never waited on with the lock held
+            app_state.toggle_listener(&app_handle, &toggle_item);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn matches_recorders() {
+        assert!(matches_process(
+            "obs",
+            &command("/usr/bin/obs --startrecording")
+        ));
+        assert!(matches_process(
+            "obs64",
+            &command("C:\\Program Files\\obs64.exe")
+        ));
+        assert!(!matches_process("obs", &command("/usr/bin/jobs")));
+        assert!(matches_process(
+            "ffmpeg x11grab",
+            &command("ffmpeg -f x11grab -i :0.0 out.mkv")
+        ));
+        assert!(!matches_process(
+            "ffmpeg x11grab",
+            &command("ffmpeg -i in.mp4 out.webm")
+        ));
+    }
+
+    #[test]
+    fn evaluates_rules() {
+        let rules = ActivationRules {
+            enabled: true,
+            processes: vec!["obs".to_string()],
+            apps: vec!["Code".to_string()],
+            from: Some("22:00".to_string()),
+            to: Some("02:00".to_string()),
+        };
+        let noon = 12 * 60;
+        assert!(!rules.evaluate(noon, Some("firefox"), &[command("bash")]));
+        assert!(rules.evaluate(noon, Some("code"), &[]));
+        assert!(rules.evaluate(noon, None, &[command("/usr/bin/obs")]));
+        assert!(rules.evaluate(23 * 60, None, &[]));
+        assert!(rules.evaluate(60, None, &[]));
+        assert!(!rules.evaluate(2 * 60, None, &[]));
+
+        assert_eq!(parse_time("9:05"), Some(545));
+        assert_eq!(parse_time("24:00"), None);
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn parses_procargs() {
+        let mut buffer = 2i32.to_ne_bytes().to_vec();
+        buffer.extend_from_slice(b"/usr/bin/ffmpeg\0\0\0ffmpeg\0x11grab\0PATH=/usr/bin\0");
+        assert_eq!(parse_procargs(&buffer), command("ffmpeg x11grab"));
+    }
+}
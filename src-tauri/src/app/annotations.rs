@@ -0,0 +1,249 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use serde::Deserialize;
+use tauri::{AppHandle, Manager};
+
+use crate::app::event::InputEvent;
+use crate::app::headless::ComboFormatter;
+
+const MODIFIER_ORDER: [&str; 4] = ["ctrl", "alt", "shift", "meta"];
+
+// shipped with keyviz, the user's own files are loaded after and win
+const BUNDLES: &[&str] = &[
+    include_str!("../../annotations/common.json"),
+    include_str!("../../annotations/common-macos.json"),
+    include_str!("../../annotations/vscode.json"),
+    include_str!("../../annotations/vscode-macos.json"),
+    include_str!("../../annotations/jetbrains.json"),
+];
+
+/// One annotation file, e.g.
+/// `{"apps": ["code"], "shortcuts": {"Ctrl+Shift+P": "Command Palette"}}`.
+#[derive(Debug, Deserialize)]
+struct AnnotationFile {
+    /// foreground apps the shortcuts apply to, all apps when empty
+    #[serde(default)]
+    apps: Vec<String>,
+    /// `std::env::consts::OS` values, every platform when empty
+    #[serde(default)]
+    os: Vec<String>,
+    shortcuts: HashMap<String, String>,
+}
+
+/// Chords mapped to what they do, globally or for specific apps.
+#[derive(Debug, Default)]
+pub struct AnnotationDb {
+    global: HashMap<String, String>,
+    scoped: Vec<(Vec<String>, HashMap<String, String>)>,
+}
+
+impl AnnotationDb {
+    /// Adds the shortcuts of an annotation file, replacing earlier ones.
+    pub fn add(&mut self, content: &str) -> Result<(), String> {
+        let file: AnnotationFile = serde_json::from_str(content).map_err(|e| e.to_string())?;
+        if !file.os.is_empty() && !file.os.iter().any(|os| os == std::env::consts::OS) {
+            return Ok(());
+        }
+
+        let mut shortcuts = HashMap::new();
+        for (chord, description) in file.shortcuts {
+            match normalize_chord(&chord) {
+                Some(chord) => {
+                    shortcuts.insert(chord, description);
+                }
+                None => eprintln!("Ignoring annotation for {:?}", chord),
+            }
+        }
+        if file.apps.is_empty() {
+            self.global.extend(shortcuts);
+        } else {
+            let apps = file.apps.iter().map(|app| app.to_lowercase()).collect();
+            self.scoped.push((apps, shortcuts));
+        }
+        Ok(())
+    }
+
+    /// Whether any shortcut depends on the foreground app.
+    pub fn is_app_scoped(&self) -> bool {
+        !self.scoped.is_empty()
+    }
+
+    pub fn count(&self) -> usize {
+        self.global.len() + self.scoped.iter().map(|(_, s)| s.len()).sum::<usize>()
+    }
+
+    /// What `chord` does in `app`, app specific annotations first.
+    pub fn describe(&self, chord: &str, app: Option<&str>) -> Option<&str> {
+        let chord = normalize_chord(chord)?;
+        let app = app.map(str::to_lowercase);
+        self.scoped
+            .iter()
+            .rev()
+            .filter(|(apps, _)| app.as_ref().is_some_and(|app| apps.contains(app)))
+            .find_map(|(_, shortcuts)| shortcuts.get(&chord))
+            .or_else(|| self.global.get(&chord))
+            .map(String::as_str)
+    }
+}
+
+/// Canonical form of a chord so "Ctrl+Shift+P", "shift+control+p" and
+/// rdev's "ControlLeft+ShiftLeft+KeyP" all compare equal.
+pub fn normalize_chord(chord: &str) -> Option<String> {
+    let mut modifiers = vec![];
+    let mut keys = vec![];
+    for part in chord.split('+') {
+        let key = normalize_key(part)?;
+        if MODIFIER_ORDER.contains(&key.as_str()) {
+            if !modifiers.contains(&key) {
+                modifiers.push(key);
+            }
+        } else {
+            keys.push(key);
+        }
+    }
+    if keys.is_empty() && modifiers.is_empty() {
+        return None;
+    }
+    modifiers.sort_by_key(|m| MODIFIER_ORDER.iter().position(|o| o == m));
+    modifiers.extend(keys);
+    Some(modifiers.join("+"))
+}
+
+fn normalize_key(key: &str) -> Option<String> {
+    let key = key.trim().to_lowercase();
+    let key = match key.as_str() {
+        "" => return None,
+        "ctrl" | "ctl" | "control" | "controlleft" | "controlright" => "ctrl",
+        "alt" | "altgr" | "option" | "opt" => "alt",
+        "shift" | "shiftleft" | "shiftright" => "shift",
+        "meta" | "metaleft" | "metaright" | "cmd" | "command" | "super" | "win" => "meta",
+        "enter" => "return",
+        "esc" => "escape",
+        "del" => "delete",
+        "up" | "down" | "left" | "right" => return Some(format!("{}arrow", key)),
+        "`" => "backquote",
+        "-" => "minus",
+        "=" => "equal",
+        "[" => "leftbracket",
+        "]" => "rightbracket",
+        ";" => "semicolon",
+        "'" => "quote",
+        "\\" => "backslash",
+        "," => "comma",
+        "." => "dot",
+        "/" => "slash",
+        // rdev's KeyA and Num1
+        k if k.len() == 4 && k.starts_with("key") => &k[3..],
+        k if k.len() == 4 && k.starts_with("num") && k.as_bytes()[3].is_ascii_digit() => &k[3..],
+        k => k,
+    };
+    Some(key.to_string())
+}
+
+fn annotations_dir(app: &AppHandle) -> Option<PathBuf> {
+    Some(app.path().app_config_dir().ok()?.join("annotations"))
+}
+
+/// The bundled annotations plus `*.json` files in `<config dir>/annotations`.
+pub fn load_annotations(app: &AppHandle) -> AnnotationDb {
+    let mut db = AnnotationDb::default();
+    for bundle in BUNDLES {
+        db.add(bundle).unwrap();
+    }
+
+    let Some(dir) = annotations_dir(app) else {
+        return db;
+    };
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                .collect()
+        })
+        .unwrap_or_default();
+    paths.sort();
+    for path in paths {
+        let result = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| db.add(&content));
+        if let Err(e) = result {
+            eprintln!("Failed to load annotations {:?}: {}", path, e);
+        }
+    }
+    println!("📝 Loaded {} shortcut annotations", db.count());
+    db
+}
+
+/// Follows the key stream and tells what each combo does.
+#[derive(Default)]
+pub struct Annotator {
+    pub enabled: bool,
+    pub db: AnnotationDb,
+    combos: ComboFormatter,
+}
+
+impl Annotator {
+    pub fn describe(&mut self, event: &InputEvent, app: Option<&str>) -> Option<InputEvent> {
+        // keep the formatter in step even for keys without annotation
+        let combo = match event {
+            InputEvent::KeyEvent { .. } => self.combos.format(event)?,
+            _ => return None,
+        };
+        if !self.enabled {
+            return None;
+        }
+        let description = self.db.describe(&combo, app)?;
+        Some(InputEvent::ComboEvent {
+            keys: combo.split('+').map(String::from).collect(),
+            description: description.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_chords() {
+        assert_eq!(
+            normalize_chord("ControlLeft+ShiftLeft+KeyP").as_deref(),
+            Some("ctrl+shift+p")
+        );
+        assert_eq!(
+            normalize_chord("shift + Ctrl + P").as_deref(),
+            Some("ctrl+shift+p")
+        );
+        assert_eq!(normalize_chord("Cmd+Num1").as_deref(), Some("meta+1"));
+        assert_eq!(
+            normalize_chord("Ctrl+`"),
+            normalize_chord("ControlRight+BackQuote")
+        );
+        assert_eq!(normalize_chord("Alt+Up").as_deref(), Some("alt+uparrow"));
+        assert_eq!(normalize_chord("Ctrl++"), None);
+    }
+
+    #[test]
+    fn prefers_app_annotations() {
+        let mut db = AnnotationDb::default();
+        db.add(r#"{"shortcuts": {"Ctrl+P": "Print", "Ctrl+C": "Copy"}}"#)
+            .unwrap();
+        db.add(r#"{"apps": ["Code"], "shortcuts": {"Ctrl+P": "Quick Open"}}"#)
+            .unwrap();
+
+        assert_eq!(
+            db.describe("ControlLeft+KeyP", Some("code")),
+            Some("Quick Open")
+        );
+        assert_eq!(
+            db.describe("ControlLeft+KeyP", Some("firefox")),
+            Some("Print")
+        );
+        assert_eq!(db.describe("ControlLeft+KeyP", None), Some("Print"));
+        assert_eq!(db.describe("ControlLeft+KeyC", Some("code")), Some("Copy"));
+        assert_eq!(db.describe("ControlLeft+KeyV", None), None);
+        assert!(db.is_app_scoped());
+    }
+}
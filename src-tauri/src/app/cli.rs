@@ -0,0 +1,116 @@
+use crate::app::control::ControlRequest;
+
+/// What the keyviz binary was asked to do on the command line.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliCommand {
+    /// Regular tray app with the overlay.
+    Run,
+    /// Print an environment report and exit.
+    Diagnose { json: bool },
+    /// Print input to stdout, no windows or tray.
+    Headless { json: bool },
+    /// `keyviz ctl <command>`, talk to the running app.
+    Control(Vec<String>),
+}
+
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> CliCommand {
+    // skip the binary name
+    let args: Vec<String> = args.into_iter().skip(1).collect();
+    if args.first().map(String::as_str) == Some("ctl") {
+        return CliCommand::Control(args[1..].to_vec());
+    }
+    let json = args.iter().any(|arg| arg == "--json");
+
+    if args.iter().any(|arg| arg == "--diagnose") {
+        return CliCommand::Diagnose { json };
+    }
+    if args.iter().any(|arg| arg == "--headless") {
+        return CliCommand::Headless { json };
+    }
+    CliCommand::Run
+}
+
+/// Flags a second launch forwards to the running instance, e.g.
+/// `keyviz --monitor HDMI-1 --profile coding`.
+pub fn parse_instance_args<I: IntoIterator<Item = String>>(args: I) -> Vec<ControlRequest> {
+    let mut args = args.into_iter().skip(1);
+    let mut requests = vec![];
+
+    while let Some(arg) = args.next() {
+        let (flag, value) = match arg.split_once('=') {
+            Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+            None => (arg, None),
+        };
+        let request = match flag.as_str() {
+            "--toggle" => ControlRequest::Toggle,
+            "--start" => ControlRequest::Start,
+            "--stop" => ControlRequest::Stop,
+            "--settings" => ControlRequest::Settings,
+            "--monitor" | "--profile" => {
+                let Some(value) = value.or_else(|| args.next()) else {
+                    eprintln!("{} needs a value", flag);
+                    continue;
+                };
+                if flag == "--monitor" {
+                    ControlRequest::Monitor(value)
+                } else {
+                    ControlRequest::Profile(value)
+                }
+            }
+            _ => continue,
+        };
+        requests.push(request);
+    }
+    requests
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> CliCommand {
+        parse_args(
+            std::iter::once("keyviz")
+                .chain(args.iter().copied())
+                .map(String::from),
+        )
+    }
+
+    #[test]
+    fn parses_flags() {
+        assert_eq!(parse(&[]), CliCommand::Run);
+        assert_eq!(parse(&["--diagnose"]), CliCommand::Diagnose { json: false });
+        assert_eq!(
+            parse(&["--json", "--diagnose"]),
+            CliCommand::Diagnose { json: true }
+        );
+        assert_eq!(
+            parse(&["--headless", "--json"]),
+            CliCommand::Headless { json: true }
+        );
+        assert_eq!(
+            parse(&["ctl", "profile", "--json"]),
+            CliCommand::Control(vec!["profile".to_string(), "--json".to_string()])
+        );
+    }
+
+    #[test]
+    fn parses_instance_args() {
+        let args = [
+            "keyviz",
+            "--toggle",
+            "--monitor",
+            "HDMI-1",
+            "--profile=coding",
+            "--x",
+        ];
+        assert_eq!(
+            parse_instance_args(args.iter().map(|arg| arg.to_string())),
+            vec![
+                ControlRequest::Toggle,
+                ControlRequest::Monitor("HDMI-1".to_string()),
+                ControlRequest::Profile("coding".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_MOUSE_MOVE_RATE: u32 = 60;
+
+/// Throttles mouse moves to a fixed rate while always keeping the latest position.
+pub struct MouseMoveCoalescer {
+    interval: Duration,
+    last_emit: Option<Instant>,
+    pending: Option<(f64, f64)>,
+    emitted: u64,
+    dropped: u64,
+}
+
+impl MouseMoveCoalescer {
+    pub fn new(rate: u32) -> Self {
+        Self {
+            interval: interval_for(rate),
+            last_emit: None,
+            pending: None,
+            emitted: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Moves per second, `0` disables coalescing.
+    pub fn set_rate(&mut self, rate: u32) {
+        self.interval = interval_for(rate);
+    }
+
+    /// How long until the held position is due, `None` while nothing is
+    /// held so the caller can sleep until the next event.
+    pub fn due_in(&self, now: Instant) -> Option<Duration> {
+        self.pending?;
+        let elapsed = match self.last_emit {
+            Some(last) => now.duration_since(last),
+            None => self.interval,
+        };
+        Some(self.interval.saturating_sub(elapsed))
+    }
+
+    /// Records a move and returns the position to emit right away, if the
+    /// interval since the last emit has elapsed.
+    pub fn push(&mut self, x: f64, y: f64, now: Instant) -> Option<(f64, f64)> {
+        if self.pending.replace((x, y)).is_some() {
+            self.dropped += 1;
+        }
+        self.flush(now)
+    }
+
+    /// Returns the held position once its interval has elapsed, so the last
+    /// move before the mouse stops is never lost.
+    pub fn flush(&mut self, now: Instant) -> Option<(f64, f64)> {
+        let due = match self.last_emit {
+            Some(last) => now.duration_since(last) >= self.interval,
+            None => true,
+        };
+        if !due {
+            return None;
+        }
+        let position = self.pending.take()?;
+        self.last_emit = Some(now);
+        self.emitted += 1;
+        Some(position)
+    }
+
+    /// Returns the held position immediately, used before button events so
+    /// clicks are shown where they happened.
+    pub fn take(&mut self, now: Instant) -> Option<(f64, f64)> {
+        let position = self.pending.take()?;
+        self.last_emit = Some(now);
+        self.emitted += 1;
+        Some(position)
+    }
+
+    pub fn emitted(&self) -> u64 {
+        self.emitted
+    }
+
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+}
+
+impl Default for MouseMoveCoalescer {
+    fn default() -> Self {
+        Self::new(DEFAULT_MOUSE_MOVE_RATE)
+    }
+}
+
+fn interval_for(rate: u32) -> Duration {
+    if rate == 0 {
+        Duration::ZERO
+    } else {
+        Duration::from_secs_f64(1.0 / rate as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_latest_position() {
+        let start = Instant::now();
+        let mut coalescer = MouseMoveCoalescer::new(10);
+
+        assert_eq!(coalescer.push(1.0, 1.0, start), Some((1.0, 1.0)));
+        assert_eq!(coalescer.due_in(start), None);
+        assert_eq!(coalescer.push(2.0, 2.0, start), None);
+        assert_eq!(coalescer.push(3.0, 3.0, start), None);
+        assert_eq!(
+            coalescer.due_in(start + Duration::from_millis(40)),
+            Some(Duration::from_millis(60))
+        );
+        assert_eq!(coalescer.flush(start + Duration::from_millis(50)), None);
+        assert_eq!(
+            coalescer.flush(start + Duration::from_millis(100)),
+            Some((3.0, 3.0))
+        );
+        assert_eq!(coalescer.flush(start + Duration::from_millis(300)), None);
+
+        assert_eq!(coalescer.emitted(), 2);
+        assert_eq!(coalescer.dropped(), 1);
+    }
+
+    #[test]
+    fn take_ignores_interval() {
+        let start = Instant::now();
+        let mut coalescer = MouseMoveCoalescer::new(10);
+
+        coalescer.push(1.0, 1.0, start);
+        coalescer.push(2.0, 2.0, start);
+        assert_eq!(coalescer.take(start), Some((2.0, 2.0)));
+        assert_eq!(coalescer.take(start), None);
+    }
+
+    #[test]
+    fn zero_rate_passes_through() {
+        let start = Instant::now();
+        let mut coalescer = MouseMoveCoalescer::new(0);
+
+        assert_eq!(coalescer.push(1.0, 1.0, start), Some((1.0, 1.0)));
+        assert_eq!(coalescer.push(2.0, 2.0, start), Some((2.0, 2.0)));
+        assert_eq!(coalescer.dropped(), 0);
+    }
+}
@@ -1,8 +1,27 @@
-use std::sync::Mutex;
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
-use tauri::{Manager, PhysicalPosition, PhysicalSize};
+use serde_json::json;
+use tauri::Manager;
 
+use crate::app::activation::ActivationRules;
+use crate::app::annotations::load_annotations;
+use crate::app::diagnose::DiagnosticReport;
+use crate::app::event::{ListenerMetrics, ResyncState};
+use crate::app::listener::ListenerStatus;
+use crate::app::login::{self, launch_at_login, LaunchAtLogin};
+use crate::app::monitor::{list_monitors, select_main_window_monitor};
+use crate::app::overlay::sync_span_overlays;
+use crate::app::profile;
+use crate::app::queue::QueueCounters;
+use crate::app::sequence::SequenceDefinition;
 use crate::app::state::AppState;
+use crate::app::stats::{flush_statistics, save_statistics, Statistics};
+use crate::app::store::{patch_store_value, KEY_STYLE_STORE};
+use crate::app::theme::{self, theme_assets, Alignment, ThemeAssets};
+use crate::app::window::{save_overlay_placement, set_interactive};
 
 #[tauri::command]
 pub fn log(message: String) {
@@ -17,41 +36,300 @@ pub fn set_toggle_shortcut(app: tauri::AppHandle, shortcut: Vec<String>) {
 }
 
 #[tauri::command]
-pub fn set_main_window_monitor(app: tauri::AppHandle, monitor_name: String) {
+pub fn set_arrange_shortcut(app: tauri::AppHandle, shortcut: Vec<String>) {
     let state = app.state::<Mutex<AppState>>();
     let mut app_state = state.lock().unwrap();
+    app_state.arrange_shortcut = shortcut;
+}
+
+/// Makes the overlay take the cursor so the keystrokes can be dragged.
+#[tauri::command]
+pub fn set_interactive_mode(app: tauri::AppHandle, enabled: bool) {
+    let state = app.state::<Mutex<AppState>>();
+    let mut app_state = state.lock().unwrap();
+    set_interactive(&mut app_state, &app, enabled);
+}
 
-    if app_state.monitor_name == Some(monitor_name.clone()) {
-        return;
+/// Where the keystrokes were dropped, ends the interactive mode.
+#[tauri::command]
+pub fn set_overlay_placement(
+    app: tauri::AppHandle,
+    alignment: Alignment,
+    margin_x: f64,
+    margin_y: f64,
+) -> Result<(), String> {
+    {
+        let state = app.state::<Mutex<AppState>>();
+        let mut app_state = state.lock().unwrap();
+        set_interactive(&mut app_state, &app, false);
     }
+    save_overlay_placement(&app, alignment, margin_x, margin_y)
+}
 
-    if let Some(window) = app.get_webview_window("main") {
-        let monitors = window.available_monitors().unwrap_or_default();
-        let target_monitor = monitors.iter().find(|m| m.name() == Some(&monitor_name));
+#[tauri::command]
+pub async fn set_main_window_monitor(app: tauri::AppHandle, monitor_name: String) {
+    if let Err(e) = select_main_window_monitor(&app, &monitor_name) {
+        eprintln!("Failed to move overlay: {}", e);
+    }
+}
 
-        if let Some(monitor) = target_monitor {
-            let position = monitor.position();
-            let size = monitor.size();
-            let scale = monitor.scale_factor();
+/// Moves the overlay to whichever monitor the pointer is on.
+// async, a sync command runs on the main thread that answers the monitor query
+#[tauri::command]
+pub async fn set_follow_cursor(app: tauri::AppHandle, enabled: bool) {
+    // before locking, the main thread answers this and may be waiting on the lock
+    let monitors = app
+        .get_webview_window("main")
+        .filter(|_| enabled)
+        .map(|window| list_monitors(&window));
 
-            // Update AppState
-            app_state.monitor_name = Some(monitor_name.clone());
-            app_state.monitor_scale = scale;
-            app_state.monitor_position = (position.x, position.y);
+    let stop_spanning = {
+        let state = app.state::<Mutex<AppState>>();
+        let mut app_state = state.lock().unwrap();
+        app_state.follow_cursor = enabled;
+        if let Some(monitors) = monitors {
+            app_state.monitors = monitors;
+        }
+        // a single overlay follows the cursor, the two don't mix
+        let stop_spanning = enabled && app_state.span_monitors;
+        if stop_spanning {
+            app_state.span_monitors = false;
+        }
+        stop_spanning
+    };
 
-            // Update window
-            window
-                .set_position(PhysicalPosition {
-                    x: position.x,
-                    y: position.y,
-                })
-                .unwrap_or(());
-            window
-                .set_size(PhysicalSize {
-                    width: size.width,
-                    height: size.height,
-                })
-                .unwrap_or(());
+    if stop_spanning {
+        sync_span_overlays(&app);
+        // uncheck it in the settings too
+        if let Err(e) = patch_store_value(
+            &app,
+            KEY_STYLE_STORE,
+            "appearance",
+            json!({ "spanMonitors": false }),
+        ) {
+            eprintln!("Failed to save span monitors: {}", e);
         }
     }
 }
+
+/// Opens a mouse-only overlay on every other monitor, not while the overlay
+/// follows the cursor.
+#[tauri::command]
+pub fn set_span_monitors(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    {
+        let state = app.state::<Mutex<AppState>>();
+        let mut app_state = state.lock().unwrap();
+        if enabled && app_state.follow_cursor {
+            return Err("can't span monitors while following the cursor".to_string());
+        }
+        if app_state.span_monitors == enabled {
+            return Ok(());
+        }
+        app_state.span_monitors = enabled;
+    }
+    sync_span_overlays(&app);
+    Ok(())
+}
+
+/// Releases keys held longer than `seconds`, `0` disables the timeout.
+#[tauri::command]
+pub fn set_stuck_key_timeout(app: tauri::AppHandle, seconds: u64) {
+    let state = app.state::<Mutex<AppState>>();
+    let mut app_state = state.lock().unwrap();
+    app_state.stuck_key_timeout = (seconds > 0).then(|| Duration::from_secs(seconds));
+}
+
+/// Mouse moves sent per second, `0` sends every one.
+#[tauri::command]
+pub fn set_mouse_move_rate(app: tauri::AppHandle, rate: u32) {
+    let state = app.state::<Mutex<AppState>>();
+    let mut app_state = state.lock().unwrap();
+    app_state.mouse_coalescer.set_rate(rate);
+}
+
+/// Events sent versus coalesced or lost, shown with the diagnostics.
+#[tauri::command]
+pub fn get_listener_metrics(app: tauri::AppHandle) -> ListenerMetrics {
+    let counters = app.state::<Arc<QueueCounters>>();
+    let state = app.state::<Mutex<AppState>>();
+    let app_state = state.lock().unwrap();
+    ListenerMetrics {
+        mouse_moves_emitted: app_state.mouse_coalescer.emitted(),
+        mouse_moves_dropped: app_state.mouse_coalescer.dropped(),
+        events_queued: counters.queued(),
+        events_overflowed: counters.overflowed(),
+    }
+}
+
+/// Environment report for the About page, same as `keyviz --diagnose`.
+#[tauri::command]
+pub fn diagnose() -> DiagnosticReport {
+    crate::app::diagnose::diagnose()
+}
+
+#[tauri::command]
+pub fn get_listener_status(app: tauri::AppHandle) -> ListenerStatus {
+    let state = app.state::<Mutex<AppState>>();
+    let app_state = state.lock().unwrap();
+    app_state.listener_status.clone()
+}
+
+#[tauri::command]
+pub fn resync(app: tauri::AppHandle, window: tauri::WebviewWindow) -> ResyncState {
+    let state = app.state::<Mutex<AppState>>();
+    let app_state = state.lock().unwrap();
+    // mouse-only overlays keep their own numbering and never show keys
+    let overlay = app_state
+        .overlays
+        .iter()
+        .find(|o| o.label == window.label());
+    ResyncState {
+        seq: overlay.map_or(app_state.stream.seq(), |o| o.stream.seq()),
+        listening: app_state.listening,
+        pressed_keys: match overlay {
+            Some(_) => vec![],
+            None => app_state.pressed_keys.clone(),
+        },
+        pressed_buttons: app_state.pressed_buttons.clone(),
+        mouse_x: app_state.mouse_position.0,
+        mouse_y: app_state.mouse_position.1,
+    }
+}
+
+/// Shows what combos do under the keys, reloading the annotation files.
+#[tauri::command]
+pub fn set_annotations_enabled(app: tauri::AppHandle, enabled: bool) {
+    let db = enabled.then(|| load_annotations(&app));
+    let state = app.state::<Mutex<AppState>>();
+    let mut app_state = state.lock().unwrap();
+    app_state.annotator.enabled = enabled;
+    if let Some(db) = db {
+        app_state.annotator.db = db;
+    }
+}
+
+/// Sequences like `g g` to detect, `timeout_ms` being the longest pause
+/// between their steps.
+#[tauri::command]
+pub fn set_sequences(app: tauri::AppHandle, sequences: Vec<SequenceDefinition>, timeout_ms: u64) {
+    let state = app.state::<Mutex<AppState>>();
+    let mut app_state = state.lock().unwrap();
+    app_state.sequences.set_sequences(&sequences);
+    app_state.sequences.timeout = Duration::from_millis(timeout_ms);
+}
+
+/// Pauses the visualization after `timeout_secs` without input, 0 never
+/// does, hiding the overlays too with `hide_windows`.
+#[tauri::command]
+pub fn set_idle(app: tauri::AppHandle, timeout_secs: u64, hide_windows: bool) {
+    let state = app.state::<Mutex<AppState>>();
+    let mut app_state = state.lock().unwrap();
+    app_state.idle.timeout = (timeout_secs > 0).then_some(Duration::from_secs(timeout_secs));
+    app_state.idle.hide_windows = hide_windows;
+}
+
+/// Conditions that start and stop the visualization on their own.
+#[tauri::command]
+pub fn set_activation_rules(app: tauri::AppHandle, rules: ActivationRules) {
+    let state = app.state::<Mutex<AppState>>();
+    let mut app_state = state.lock().unwrap();
+    app_state.activation = rules;
+}
+
+#[tauri::command]
+pub fn get_launch_at_login() -> LaunchAtLogin {
+    launch_at_login()
+}
+
+/// Adds or removes the login entry, `start_paused` starting with
+/// visualization off.
+#[tauri::command]
+pub fn set_launch_at_login(setting: LaunchAtLogin) -> Result<(), String> {
+    login::set_launch_at_login(setting)
+}
+
+/// Sends OS auto-repeats of held keys to the webview.
+#[tauri::command]
+pub fn set_key_repeats(app: tauri::AppHandle, enabled: bool) {
+    let state = app.state::<Mutex<AppState>>();
+    let mut app_state = state.lock().unwrap();
+    app_state.key_repeats = enabled;
+}
+
+/// Shows typed text as a ticker, never while an app in `private_apps` is
+/// focused.
+#[tauri::command]
+pub fn set_typing_mode(app: tauri::AppHandle, enabled: bool, private_apps: Vec<String>) {
+    let state = app.state::<Mutex<AppState>>();
+    let mut app_state = state.lock().unwrap();
+    app_state.typing.enabled = enabled;
+    app_state.typing.set_private_apps(&private_apps);
+}
+
+/// Opt-in key usage statistics, saved on disable so nothing is lost.
+#[tauri::command]
+pub fn set_statistics_enabled(app: tauri::AppHandle, enabled: bool) {
+    {
+        let state = app.state::<Mutex<AppState>>();
+        let mut app_state = state.lock().unwrap();
+        app_state.stats.enabled = enabled;
+    }
+    if !enabled {
+        flush_statistics(&app);
+    }
+}
+
+/// Collected statistics between two dates ("YYYY-MM-DD"), both inclusive.
+#[tauri::command]
+pub fn get_statistics(
+    app: tauri::AppHandle,
+    from: Option<String>,
+    to: Option<String>,
+) -> Statistics {
+    let state = app.state::<Mutex<AppState>>();
+    let app_state = state.lock().unwrap();
+    app_state.stats.stats.range(from.as_deref(), to.as_deref())
+}
+
+#[tauri::command]
+pub fn export_statistics(app: tauri::AppHandle, path: String) -> Result<(), String> {
+    let stats = {
+        let state = app.state::<Mutex<AppState>>();
+        let app_state = state.lock().unwrap();
+        app_state.stats.stats.clone()
+    };
+    let content = serde_json::to_string_pretty(&stats).map_err(|e| e.to_string())?;
+    std::fs::write(path, content).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn clear_statistics(app: tauri::AppHandle) -> Result<(), String> {
+    let state = app.state::<Mutex<AppState>>();
+    let mut app_state = state.lock().unwrap();
+    app_state.stats.stats = Statistics::default();
+    app_state.stats.dirty = false;
+    save_statistics(&app, &app_state.stats.stats)
+}
+
+/// Validates a `.json` theme or `.keyviz-theme` archive and switches to it.
+#[tauri::command]
+pub fn import_theme(app: tauri::AppHandle, path: String) -> Result<(), String> {
+    theme::import_theme(&app, std::path::Path::new(&path))
+}
+
+/// Saves the key style, bundled with fonts and icons for `.keyviz-theme`.
+#[tauri::command]
+pub fn export_theme(app: tauri::AppHandle, path: String) -> Result<(), String> {
+    theme::export_theme(&app, std::path::Path::new(&path))
+}
+
+#[tauri::command]
+pub fn get_theme_assets(app: tauri::AppHandle) -> ThemeAssets {
+    theme_assets(&app)
+}
+
+/// Saves the current key style as a profile for the tray and `keyviz ctl`.
+#[tauri::command]
+pub fn save_profile(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    profile::save_profile(&app, name.trim())
+}
@@ -0,0 +1,317 @@
+use std::sync::Mutex;
+
+use serde_json::{json, Value};
+use tauri::{menu::MenuItem, AppHandle, Manager, Wry};
+
+use crate::app::monitor::select_main_window_monitor;
+use crate::app::profile::{apply_profile, list_profiles, save_profile};
+use crate::app::recording::{start_recording, stop_recording};
+use crate::app::state::AppState;
+use crate::app::store::{patch_store_value, KEY_STYLE_STORE};
+use crate::app::window::open_settings_window;
+
+/// One line of the control protocol, e.g. `monitor DELL U2720Q`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ControlRequest {
+    Start,
+    Stop,
+    Toggle,
+    Status,
+    Monitor(String),
+    Profiles,
+    Profile(String),
+    SaveProfile(String),
+    /// starts recording, to a new file in the videos folder without a path
+    Record(Option<String>),
+    RecordStop,
+    Settings,
+}
+
+pub fn parse_request(line: &str) -> Result<ControlRequest, String> {
+    let line = line.trim();
+    let (command, arg) = match line.split_once(char::is_whitespace) {
+        Some((command, arg)) => (command, arg.trim()),
+        None => (line, ""),
+    };
+    let required = |what: &str| {
+        if arg.is_empty() {
+            Err(format!("usage: {} <{}>", command, what))
+        } else {
+            Ok(arg.to_string())
+        }
+    };
+
+    match command {
+        "start" => Ok(ControlRequest::Start),
+        "stop" => Ok(ControlRequest::Stop),
+        "toggle" => Ok(ControlRequest::Toggle),
+        "status" => Ok(ControlRequest::Status),
+        "monitor" => required("name").map(ControlRequest::Monitor),
+        "profiles" => Ok(ControlRequest::Profiles),
+        "profile" => required("name").map(ControlRequest::Profile),
+        "save-profile" => required("name").map(ControlRequest::SaveProfile),
+        "record" => Ok(ControlRequest::Record(
+            (!arg.is_empty()).then(|| arg.to_string()),
+        )),
+        "record-stop" => Ok(ControlRequest::RecordStop),
+        "settings" => Ok(ControlRequest::Settings),
+        "" => Err("empty request".to_string()),
+        _ => Err(format!("unknown command {:?}", command)),
+    }
+}
+
+pub fn execute(
+    app: &AppHandle,
+    toggle_menu_item: &MenuItem<Wry>,
+    request: ControlRequest,
+) -> Result<Value, String> {
+    let state = app.state::<Mutex<AppState>>();
+
+    match request {
+        ControlRequest::Start | ControlRequest::Stop | ControlRequest::Toggle => {
+            let mut app_state = state.lock().unwrap();
+            let listening = match request {
+                ControlRequest::Start => true,
+                ControlRequest::Stop => false,
+                _ => !app_state.listening,
+            };
+            // the menu and tray are updated on the main thread after the
+            // lock is gone, a sync command waiting on it can't hold us up
+            if app_state.listening != listening {
+                app_state.toggle_listener(app, toggle_menu_item);
+            }
+            Ok(json!({ "listening": listening }))
+        }
+        ControlRequest::Status => {
+            let app_state = state.lock().unwrap();
+            Ok(json!({
+                "listening": app_state.listening,
+                "listener": app_state.listener_status,
+                "monitor": app_state.monitor_name,
+                "recording": app_state.recorder.path(),
+            }))
+        }
+        ControlRequest::Monitor(name) => {
+            select_main_window_monitor(app, &name)?;
+            // keep the settings window in agreement, it isn't fatal if
+            // the appearance was never saved
+            if let Err(e) = patch_store_value(
+                app,
+                KEY_STYLE_STORE,
+                "appearance",
+                json!({ "monitor": name }),
+            ) {
+                eprintln!("Failed to save monitor: {}", e);
+            }
+            Ok(json!({ "monitor": name }))
+        }
+        ControlRequest::Profiles => Ok(json!(list_profiles(app))),
+        ControlRequest::Profile(name) => {
+            apply_profile(app, &name)?;
+            Ok(json!({ "profile": name }))
+        }
+        ControlRequest::SaveProfile(name) => {
+            save_profile(app, &name)?;
+            Ok(json!({ "saved": name }))
+        }
+        ControlRequest::Record(path) => {
+            let path = start_recording(app, path.map(Into::into))?;
+            Ok(json!({ "recording": path }))
+        }
+        ControlRequest::RecordStop => {
+            let path = stop_recording(app).ok_or("not recording")?;
+            Ok(json!({ "saved": path }))
+        }
+        ControlRequest::Settings => {
+            open_settings_window(app);
+            Ok(json!({ "settings": true }))
+        }
+    }
+}
+
+/// `keyviz/keyviz.sock` under `$XDG_RUNTIME_DIR`, or the per-user `$TMPDIR`
+/// on macOS. There's no shared fallback another user could squat.
+#[cfg(unix)]
+pub fn socket_path() -> Result<std::path::PathBuf, String> {
+    let runtime_dir = std::env::var_os("XDG_RUNTIME_DIR")
+        .or_else(|| {
+            cfg!(target_os = "macos")
+                .then(|| std::env::var_os("TMPDIR"))
+                .flatten()
+        })
+        .filter(|dir| !dir.is_empty())
+        .ok_or("XDG_RUNTIME_DIR is not set")?;
+    Ok(std::path::PathBuf::from(runtime_dir)
+        .join("keyviz")
+        .join("keyviz.sock"))
+}
+
+/// Creates the socket's directory, or checks the one already there, so only
+/// this user can reach the socket from the moment it's bound.
+#[cfg(unix)]
+fn private_dir(dir: &std::path::Path) -> Result<(), String> {
+    use std::{
+        fs, io,
+        os::unix::fs::{DirBuilderExt, MetadataExt},
+    };
+
+    if let Err(e) = fs::DirBuilder::new().mode(0o700).create(dir) {
+        if e.kind() != io::ErrorKind::AlreadyExists {
+            return Err(e.to_string());
+        }
+    }
+    // not following links, one could point anywhere
+    let meta = fs::symlink_metadata(dir).map_err(|e| e.to_string())?;
+    // SAFETY: getuid has no preconditions and can't fail
+    let uid = unsafe { libc::getuid() };
+    if !meta.is_dir() || meta.uid() != uid || meta.mode() & 0o077 != 0 {
+        return Err(format!("{:?} isn't a directory only you can access", dir));
+    }
+    Ok(())
+}
+
+/// Accepts newline separated requests on a Unix socket and answers each
+/// with a JSON line: `{"ok":true,"result":...}` or `{"ok":false,"error":...}`.
+#[cfg(unix)]
+pub fn start_control_server(app_handle: AppHandle, toggle_menu_item: MenuItem<Wry>) {
+    use std::{
+        fs,
+        io::{BufRead, BufReader, Write},
+        os::unix::net::UnixListener,
+        thread,
+    };
+
+    // other users shouldn't be able to drive the overlay
+    let path = match socket_path().and_then(|path| {
+        private_dir(path.parent().ok_or("no socket directory")?)?;
+        Ok(path)
+    }) {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("Control socket disabled: {}", e);
+            return;
+        }
+    };
+    // left behind by a previous run, single instance makes sure it isn't live
+    let _ = fs::remove_file(&path);
+    let listener = match UnixListener::bind(&path) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Failed to open control socket {:?}: {}", path, e);
+            return;
+        }
+    };
+    println!("🔌 Control socket at {:?}", path);
+
+    thread::spawn(move || {
+        for stream in listener.incoming().filter_map(Result::ok) {
+            let app_handle = app_handle.clone();
+            let toggle_menu_item = toggle_menu_item.clone();
+            thread::spawn(move || {
+                let mut writer = match stream.try_clone() {
+                    Ok(writer) => writer,
+                    Err(_) => return,
+                };
+                for line in BufReader::new(stream).lines().map_while(Result::ok) {
+                    let response = match parse_request(&line)
+                        .and_then(|request| execute(&app_handle, &toggle_menu_item, request))
+                    {
+                        Ok(result) => json!({ "ok": true, "result": result }),
+                        Err(error) => json!({ "ok": false, "error": error }),
+                    };
+                    if writeln!(writer, "{}", response).is_err() {
+                        return;
+                    }
+                }
+            });
+        }
+    });
+}
+
+/// `keyviz ctl <command>`: sends one request to the running app and prints
+/// the answer. Returns the process exit code.
+#[cfg(unix)]
+pub fn run_client(args: &[String]) -> i32 {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        os::unix::net::UnixStream,
+    };
+
+    let path = match socket_path() {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("Could not find keyviz: {}", e);
+            return 1;
+        }
+    };
+    let mut stream = match UnixStream::connect(&path) {
+        Ok(stream) => stream,
+        Err(e) => {
+            eprintln!("Could not reach keyviz at {:?}: {}", path, e);
+            return 1;
+        }
+    };
+    if let Err(e) = writeln!(stream, "{}", args.join(" ")) {
+        eprintln!("Failed to send request: {}", e);
+        return 1;
+    }
+
+    let mut line = String::new();
+    let _ = BufReader::new(stream).read_line(&mut line);
+    let response: Value = match serde_json::from_str(&line) {
+        Ok(response) => response,
+        Err(_) => {
+            eprintln!("Unexpected response: {:?}", line);
+            return 1;
+        }
+    };
+    if response["ok"] == true {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&response["result"]).unwrap()
+        );
+        0
+    } else {
+        eprintln!("{}", response["error"].as_str().unwrap_or("request failed"));
+        1
+    }
+}
+
+#[cfg(not(unix))]
+pub fn run_client(_args: &[String]) -> i32 {
+    eprintln!("The control socket is only available on Linux and macOS");
+    1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_requests() {
+        assert_eq!(parse_request("stop\n"), Ok(ControlRequest::Stop));
+        assert_eq!(
+            parse_request("monitor  DELL U2720Q "),
+            Ok(ControlRequest::Monitor("DELL U2720Q".to_string()))
+        );
+        assert_eq!(
+            parse_request("profile coding"),
+            Ok(ControlRequest::Profile("coding".to_string()))
+        );
+        assert!(parse_request("profile").is_err());
+        assert_eq!(
+            parse_request("save-profile live stream"),
+            Ok(ControlRequest::SaveProfile("live stream".to_string()))
+        );
+        assert_eq!(parse_request("record"), Ok(ControlRequest::Record(None)));
+        assert_eq!(
+            parse_request("record /tmp/demo keys.jsonl"),
+            Ok(ControlRequest::Record(Some(
+                "/tmp/demo keys.jsonl".to_string()
+            )))
+        );
+        assert_eq!(parse_request("record-stop"), Ok(ControlRequest::RecordStop));
+        assert!(parse_request("").is_err());
+        assert!(parse_request("reboot").is_err());
+    }
+}
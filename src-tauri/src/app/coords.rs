@@ -0,0 +1,140 @@
+use crate::app::monitor::MonitorInfo;
+
+/// Unit of the root coordinates rdev reports for the pointer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PointerSpace {
+    /// Device pixels of the virtual screen (Windows, X11).
+    Physical,
+    /// Display points, where each monitor's origin is its physical
+    /// position divided by its own scale factor (macOS).
+    Points,
+}
+
+impl PointerSpace {
+    pub const fn native() -> Self {
+        if cfg!(target_os = "macos") {
+            PointerSpace::Points
+        } else {
+            PointerSpace::Physical
+        }
+    }
+}
+
+/// Area a monitor covers, in pointer space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub left: f64,
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+}
+
+impl Bounds {
+    pub fn contains(&self, x: f64, y: f64, margin: f64) -> bool {
+        x >= self.left - margin
+            && x < self.right + margin
+            && y >= self.top - margin
+            && y < self.bottom + margin
+    }
+}
+
+pub fn monitor_bounds(space: PointerSpace, monitor: &MonitorInfo) -> Bounds {
+    let scale = match space {
+        PointerSpace::Physical => 1.0,
+        PointerSpace::Points => monitor.scale,
+    };
+    let left = monitor.position.0 as f64 / scale;
+    let top = monitor.position.1 as f64 / scale;
+
+    Bounds {
+        left,
+        top,
+        right: left + monitor.size.0 as f64 / scale,
+        bottom: top + monitor.size.1 as f64 / scale,
+    }
+}
+
+/// Converts a pointer position to logical (CSS) pixels relative to the top
+/// left corner of the overlay covering the monitor at `origin`.
+pub fn to_overlay(
+    space: PointerSpace,
+    origin: (i32, i32),
+    scale: f64,
+    x: f64,
+    y: f64,
+) -> (f64, f64) {
+    let scale = if scale > 0.0 { scale } else { 1.0 };
+    let (origin_x, origin_y) = (origin.0 as f64 / scale, origin.1 as f64 / scale);
+
+    match space {
+        PointerSpace::Physical => (x / scale - origin_x, y / scale - origin_y),
+        PointerSpace::Points => (x - origin_x, y - origin_y),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(position: (i32, i32), size: (u32, u32), scale: f64) -> MonitorInfo {
+        MonitorInfo {
+            name: None,
+            position,
+            size,
+            scale,
+        }
+    }
+
+    #[test]
+    fn physical_mixed_dpi() {
+        // 2x laptop panel with a 1x external monitor on its right
+        let laptop = monitor((0, 0), (2560, 1600), 2.0);
+        let external = monitor((2560, 0), (1920, 1080), 1.0);
+        let space = PointerSpace::Physical;
+
+        assert!(monitor_bounds(space, &laptop).contains(1280.0, 800.0, 0.0));
+        assert!(monitor_bounds(space, &external).contains(2600.0, 100.0, 0.0));
+        assert!(!monitor_bounds(space, &laptop).contains(2600.0, 100.0, 0.0));
+
+        let on_laptop = to_overlay(space, laptop.position, laptop.scale, 1280.0, 800.0);
+        assert_eq!(on_laptop, (640.0, 400.0));
+        let on_external = to_overlay(space, external.position, external.scale, 2600.0, 100.0);
+        assert_eq!(on_external, (40.0, 100.0));
+    }
+
+    #[test]
+    fn points_mixed_dpi() {
+        // 1x main display, 2x monitor on its right and 1x monitor on its left;
+        // macOS reports 1920pt as the right one's origin, tauri 3840px
+        let main = monitor((0, 0), (1920, 1080), 1.0);
+        let right = monitor((3840, 0), (5120, 2880), 2.0);
+        let left = monitor((-1920, -200), (1920, 1080), 1.0);
+        let space = PointerSpace::Points;
+
+        let bounds = monitor_bounds(space, &right);
+        assert_eq!(
+            bounds,
+            Bounds {
+                left: 1920.0,
+                top: 0.0,
+                right: 4480.0,
+                bottom: 1440.0,
+            }
+        );
+        assert!(!monitor_bounds(space, &main).contains(2000.0, 10.0, 0.0));
+        assert!(monitor_bounds(space, &left).contains(-10.0, -100.0, 0.0));
+
+        assert_eq!(
+            to_overlay(space, right.position, right.scale, 2000.0, 10.0),
+            (80.0, 10.0)
+        );
+        assert_eq!(
+            to_overlay(space, left.position, left.scale, -10.0, -100.0),
+            (1910.0, 100.0)
+        );
+        assert_eq!(
+            to_overlay(space, main.position, main.scale, 100.0, 50.0),
+            (100.0, 50.0)
+        );
+    }
+}
@@ -0,0 +1,263 @@
+use std::fmt;
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DisplayServer {
+    X11,
+    Wayland,
+    Windows,
+    Macos,
+    Unknown,
+}
+
+/// Environment report explaining why input capture may not work.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticReport {
+    pub os: &'static str,
+    pub display_server: DisplayServer,
+    /// how rdev captures input on this platform
+    pub backend: &'static str,
+    pub checks: Vec<Check>,
+}
+
+impl fmt::Display for DiagnosticReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Keyviz diagnostics")?;
+        writeln!(f, "  os:             {}", self.os)?;
+        writeln!(f, "  display server: {:?}", self.display_server)?;
+        writeln!(f, "  backend:        {}", self.backend)?;
+        writeln!(f)?;
+        for check in &self.checks {
+            let status = match check.status {
+                CheckStatus::Ok => "ok",
+                CheckStatus::Warn => "warn",
+                CheckStatus::Fail => "FAIL",
+            };
+            writeln!(f, "  [{:<4}] {}: {}", status, check.name, check.detail)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn diagnose() -> DiagnosticReport {
+    let mut checks = vec![];
+
+    let (display_server, backend) = match std::env::consts::OS {
+        "linux" => (
+            detect_display_server(|name| std::env::var(name).ok()),
+            "xrecord",
+        ),
+        "macos" => (DisplayServer::Macos, "cgeventtap"),
+        "windows" => (DisplayServer::Windows, "win32-hooks"),
+        _ => (DisplayServer::Unknown, "none"),
+    };
+
+    #[cfg(target_os = "linux")]
+    linux_checks(display_server, &mut checks);
+    #[cfg(target_os = "macos")]
+    macos_checks(&mut checks);
+
+    // also what the stuck key reconciler relies on
+    checks.push(match rdev::pressed_keys() {
+        Ok(_) => Check::new("Keyboard state", CheckStatus::Ok, "readable"),
+        Err(err) => Check::new("Keyboard state", CheckStatus::Warn, format!("{:?}", err)),
+    });
+
+    DiagnosticReport {
+        os: std::env::consts::OS,
+        display_server,
+        backend,
+        checks,
+    }
+}
+
+/// Guesses the Linux session type from what the desktop environment advertises.
+pub fn detect_display_server(var: impl Fn(&str) -> Option<String>) -> DisplayServer {
+    match var("XDG_SESSION_TYPE").as_deref() {
+        Some("wayland") => DisplayServer::Wayland,
+        Some("x11") => DisplayServer::X11,
+        _ if var("WAYLAND_DISPLAY").is_some() => DisplayServer::Wayland,
+        _ if var("DISPLAY").is_some() => DisplayServer::X11,
+        _ => DisplayServer::Unknown,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn linux_checks(display_server: DisplayServer, checks: &mut Vec<Check>) {
+    checks.push(match display_server {
+        DisplayServer::X11 => Check::new("Session", CheckStatus::Ok, "X11"),
+        DisplayServer::Wayland => Check::new(
+            "Session",
+            CheckStatus::Warn,
+            "Wayland, only input going to XWayland apps can be seen",
+        ),
+        _ => Check::new(
+            "Session",
+            CheckStatus::Warn,
+            "could not tell X11 from Wayland",
+        ),
+    });
+
+    let display = std::env::var("DISPLAY").unwrap_or_default();
+    match rdev::display_size() {
+        Ok((width, height)) => {
+            checks.push(Check::new(
+                "X display",
+                CheckStatus::Ok,
+                format!("connected to {:?} ({}x{})", display, width, height),
+            ));
+
+            for (extension, required) in [
+                ("RECORD", true),
+                ("XInputExtension", false),
+                ("XTEST", false),
+            ] {
+                let status = match rdev::has_extension(extension) {
+                    Ok(true) => CheckStatus::Ok,
+                    _ if required => CheckStatus::Fail,
+                    _ => CheckStatus::Warn,
+                };
+                let detail = match (status, required) {
+                    (CheckStatus::Ok, _) => "available",
+                    (_, true) => "missing, keyviz can't listen without it",
+                    (_, false) => "missing",
+                };
+                checks.push(Check::new(
+                    format!("{} extension", extension),
+                    status,
+                    detail,
+                ));
+            }
+        }
+        Err(_) => checks.push(Check::new(
+            "X display",
+            CheckStatus::Fail,
+            format!("could not connect to {:?}", display),
+        )),
+    }
+
+    // not needed by the X11 backend, but the first thing to check for evdev
+    let devices: Vec<_> = std::fs::read_dir("/dev/input")
+        .map(|dir| {
+            dir.filter_map(Result::ok)
+                .filter(|entry| entry.file_name().to_string_lossy().starts_with("event"))
+                .map(|entry| entry.path())
+                .collect()
+        })
+        .unwrap_or_default();
+    let readable = devices
+        .iter()
+        .filter(|path| std::fs::File::open(path).is_ok())
+        .count();
+    checks.push(if devices.is_empty() {
+        Check::new(
+            "Input devices",
+            CheckStatus::Warn,
+            "no /dev/input/event* devices",
+        )
+    } else if readable == devices.len() {
+        Check::new(
+            "Input devices",
+            CheckStatus::Ok,
+            format!("{} readable", readable),
+        )
+    } else {
+        Check::new(
+            "Input devices",
+            CheckStatus::Warn,
+            format!(
+                "{} of {} readable, join the `input` group for full access",
+                readable,
+                devices.len()
+            ),
+        )
+    });
+}
+
+#[cfg(target_os = "macos")]
+fn macos_checks(checks: &mut Vec<Check>) {
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXIsProcessTrusted() -> bool;
+    }
+
+    checks.push(if unsafe { AXIsProcessTrusted() } {
+        Check::new("Accessibility", CheckStatus::Ok, "granted")
+    } else {
+        Check::new(
+            "Accessibility",
+            CheckStatus::Fail,
+            "not granted, enable keyviz under Privacy & Security > Accessibility",
+        )
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_display_server() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+
+        assert_eq!(
+            detect_display_server(env(&[("XDG_SESSION_TYPE", "wayland"), ("DISPLAY", ":0")])),
+            DisplayServer::Wayland
+        );
+        assert_eq!(
+            detect_display_server(env(&[("DISPLAY", ":0")])),
+            DisplayServer::X11
+        );
+        assert_eq!(
+            detect_display_server(env(&[("WAYLAND_DISPLAY", "wayland-0")])),
+            DisplayServer::Wayland
+        );
+        assert_eq!(detect_display_server(env(&[])), DisplayServer::Unknown);
+    }
+
+    #[test]
+    fn prints_report() {
+        let report = DiagnosticReport {
+            os: "linux",
+            display_server: DisplayServer::X11,
+            backend: "xrecord",
+            checks: vec![Check::new("RECORD extension", CheckStatus::Fail, "missing")],
+        };
+        let text = report.to_string();
+        assert!(text.contains("backend:        xrecord"));
+        assert!(text.contains("[FAIL] RECORD extension: missing"));
+    }
+}
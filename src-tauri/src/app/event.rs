@@ -1,21 +1,99 @@
-use std::{sync::Mutex, thread};
+use std::{
+    sync::{
+        mpsc::{Receiver, RecvTimeoutError},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
 
-use rdev::{listen, Button, EventType};
+use rdev::{listen, Button, Event, EventType, Key};
 use serde::Serialize;
 use tauri::{menu::MenuItem, AppHandle, Emitter, Manager, Wry};
 
+use crate::app::coords::{to_overlay, PointerSpace};
+use crate::app::foreground::foreground_app;
+use crate::app::idle::leave_idle;
+use crate::app::keystate::start_key_reconciler;
+use crate::app::listener::{
+    apply_listener_status, diagnose_listen_error, retry_delay, set_listener_status, ListenerStatus,
+};
+use crate::app::monitor::follow_cursor;
+use crate::app::overlay::route_mouse;
+use crate::app::queue::{event_queue, EventSender, QueueCounters};
+use crate::app::recording::notify_recording;
 use crate::app::state::AppState;
+use crate::app::window::set_interactive;
+
+const MIN_FLUSH_INTERVAL: Duration = Duration::from_millis(4);
+const MAX_BATCH_SIZE: usize = 256;
+const STABLE_RUN: Duration = Duration::from_secs(10);
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(tag = "type")]
 pub enum InputEvent {
-    KeyEvent { pressed: bool, name: String },
-    MouseButtonEvent { pressed: bool, button: MouseButton },
-    MouseMoveEvent { x: f64, y: f64 },
-    MouseWheelEvent { delta_x: i64, delta_y: i64 },
+    KeyEvent {
+        pressed: bool,
+        name: String,
+        /// how long the key was down, on release and repeats
+        #[serde(skip_serializing_if = "Option::is_none")]
+        held_ms: Option<u64>,
+        /// OS auto-repeat of a key already down, only sent when enabled
+        #[serde(skip_serializing_if = "is_false")]
+        repeat: bool,
+    },
+    MouseButtonEvent {
+        pressed: bool,
+        button: MouseButton,
+    },
+    MouseMoveEvent {
+        x: f64,
+        y: f64,
+    },
+    MouseWheelEvent {
+        delta_x: i64,
+        delta_y: i64,
+        precise_delta_x: f64,
+        precise_delta_y: f64,
+    },
+    /// What the combo just pressed does, e.g. "Command Palette".
+    ComboEvent {
+        keys: Vec<String>,
+        description: String,
+    },
+    /// A configured key sequence completed, e.g. `Ctrl+X Ctrl+S`.
+    SequenceEvent {
+        steps: Vec<Vec<String>>,
+        name: String,
+    },
+    /// Text typed by `key`, or one character erased with `backspace`.
+    TextEvent {
+        key: String,
+        text: String,
+        backspace: bool,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ListenerMetrics {
+    pub mouse_moves_emitted: u64,
+    pub mouse_moves_dropped: u64,
+    pub events_queued: u64,
+    pub events_overflowed: u64,
 }
 
+/// Authoritative input state, for the webview to recover after a gap.
 #[derive(Debug, Clone, Serialize)]
+pub struct ResyncState {
+    pub seq: u64,
+    pub listening: bool,
+    pub pressed_keys: Vec<String>,
+    pub pressed_buttons: Vec<MouseButton>,
+    pub mouse_x: f64,
+    pub mouse_y: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum MouseButton {
     Left,
     Right,
@@ -23,6 +101,10 @@ pub enum MouseButton {
     Other,
 }
 
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
 pub fn map_mouse_button(button: Button) -> MouseButton {
     match button {
         Button::Left => MouseButton::Left,
@@ -32,105 +114,357 @@ pub fn map_mouse_button(button: Button) -> MouseButton {
     }
 }
 
+/// Where the pipeline runs, for the side effects beyond the event stream.
+pub enum Host<'a> {
+    /// The tray app with its overlay windows.
+    App {
+        app_handle: &'a AppHandle,
+        toggle_menu_item: &'a MenuItem<Wry>,
+    },
+    /// No windows or tray, see `--headless`.
+    Headless,
+}
+
 pub fn start_listener(app_handle: AppHandle, toggle_menu_item: MenuItem<Wry>) {
+    let counters = Arc::new(QueueCounters::default());
+    app_handle.manage(counters.clone());
+    let (sender, events) = event_queue(counters.clone());
+
+    start_key_reconciler(app_handle.clone());
+    start_worker(app_handle.clone(), toggle_menu_item, events, counters);
+    spawn_listen_thread(sender, move |status| {
+        set_listener_status(&app_handle, status)
+    });
+}
+
+/// Runs `rdev::listen`, restarting it with backoff when it fails.
+pub fn spawn_listen_thread(sender: EventSender, report: impl Fn(ListenerStatus) + Send + 'static) {
+    thread::spawn(move || {
+        let mut attempt = 0;
+        loop {
+            // stderr, stdout carries the events in --headless mode
+            eprintln!("Starting global input listener...");
+            if attempt == 0 {
+                report(ListenerStatus::Running);
+            }
+            let started = Instant::now();
+
+            // keep the OS hook cheap: no locks, no IPC, just hand the event over
+            let sender = sender.clone();
+            let Err(err) = listen(move |event| sender.push(event)) else {
+                break;
+            };
+            eprintln!("rdev listen failed: {:?}", err);
+
+            // it ran fine for a while, start backing off from scratch
+            if started.elapsed() > STABLE_RUN {
+                attempt = 0;
+            }
+            attempt += 1;
+            let delay = retry_delay(attempt);
+            report(ListenerStatus::Failed {
+                diagnostic: diagnose_listen_error(&err),
+                attempt,
+                retry_in_ms: delay.as_millis() as u64,
+            });
+            thread::sleep(delay);
+        }
+    });
+}
+
+/// Owns the input state machine and emitting, off the OS hook thread.
+fn start_worker(
+    app_handle: AppHandle,
+    toggle_menu_item: MenuItem<Wry>,
+    events: Receiver<Event>,
+    counters: Arc<QueueCounters>,
+) {
     thread::spawn(move || {
-        println!("Starting global input listener...");
+        let mut flush_in = None;
+        let mut overflowed = 0;
+        loop {
+            let received = recv_until(&events, flush_in);
+            if let Err(RecvTimeoutError::Disconnected) = received {
+                break;
+            }
 
-        if let Err(err) = listen(move |event| {
-            // get app state
             let state = app_handle.state::<Mutex<AppState>>();
             let mut app_state = state.lock().unwrap();
-
-            // track pressed keys
-            if let EventType::KeyPress(key) = event.event_type {
-                let key_name = format!("{:?}", key);
-                // If the name contains parenthesis (like "RawKey(123)", "Unknown()"), ignore it.
-                if key_name.contains('(') {
-                    return;
-                }
-                // if key is already marked as pressed, ignore repeat
-                if app_state.pressed_keys.contains(&key_name) {
-                    return;
+            if let Ok(event) = received {
+                // events flowing again means a restart worked
+                if app_state.listener_status.is_failed() {
+                    apply_listener_status(&mut app_state, &app_handle, ListenerStatus::Running);
                 }
-                // record key as pressed
-                app_state.pressed_keys.push(key_name);
-                // check if toggle shortcut is pressed
-                if app_state.toggle_shortcut == app_state.pressed_keys {
-                    app_state.toggle_listener(&app_handle, &toggle_menu_item);
-
-                    if !app_state.listening {
-                        // emit key releases for all pressed keys
-                        for key_name in &app_state.pressed_keys {
-                            app_handle
-                                .emit_to(
-                                    "main",
-                                    "input-event",
-                                    InputEvent::KeyEvent {
-                                        pressed: false,
-                                        name: key_name.clone(),
-                                    },
-                                )
-                                .unwrap()
-                        }
-                    }
-                }
-            } else if let EventType::KeyRelease(key) = event.event_type {
-                let key_name = format!("{:?}", key);
-                if key_name.contains('(') {
-                    return;
-                }
-                // remove key from pressed keys
-                app_state.pressed_keys.retain(|k| k != &key_name);
+                let host = Host::App {
+                    app_handle: &app_handle,
+                    toggle_menu_item: &toggle_menu_item,
+                };
+                handle_events(&mut app_state, &host, event, &events);
             }
 
-            // emit event if listening
-            if !app_state.listening {
-                return;
+            // lost events still consume sequence numbers
+            let total_overflowed = counters.overflowed();
+            if total_overflowed > overflowed {
+                app_state.stream.skip(total_overflowed - overflowed);
+                overflowed = total_overflowed;
             }
-            let input_event = match event.event_type {
-                EventType::KeyPress(key) => Some(InputEvent::KeyEvent {
-                    pressed: true,
-                    name: format!("{:?}", key),
-                }),
-                EventType::KeyRelease(key) => Some(InputEvent::KeyEvent {
-                    pressed: false,
-                    name: format!("{:?}", key),
-                }),
-                EventType::ButtonPress(button) => Some(InputEvent::MouseButtonEvent {
-                    pressed: true,
-                    button: map_mouse_button(button),
-                }),
-                EventType::ButtonRelease(button) => Some(InputEvent::MouseButtonEvent {
-                    button: map_mouse_button(button),
-                    pressed: false,
-                }),
-                EventType::MouseMove { x, y } => {
-                    // Convert Physical -> Logical
-                    #[cfg(target_os = "macos")]
-                    let (logical_x, logical_y) = (
-                        x - app_state.monitor_position.0 as f64,
-                        y - app_state.monitor_position.1 as f64,
-                    );
 
-                    #[cfg(not(target_os = "macos"))]
-                    let (logical_x, logical_y) = {
-                        let (offset_x, offset_y) = app_state.monitor_position;
-                        (x - offset_x as f64, y - offset_y as f64)
-                    };
+            flush_in = emit_batch(&mut app_state, &app_handle);
+        }
+    });
+}
+
+/// Waits for the next event, or only until `flush_in` when a coalesced
+/// mouse move is held back.
+pub fn recv_until(
+    events: &Receiver<Event>,
+    flush_in: Option<Duration>,
+) -> Result<Event, RecvTimeoutError> {
+    match flush_in {
+        Some(wait) => events.recv_timeout(wait.max(MIN_FLUSH_INTERVAL)),
+        None => events.recv().map_err(|_| RecvTimeoutError::Disconnected),
+    }
+}
 
-                    Some(InputEvent::MouseMoveEvent {
-                        x: logical_x,
-                        y: logical_y,
-                    })
+/// Handles `event` and, into the same batch, whatever piled up meanwhile.
+pub fn handle_events(
+    app_state: &mut AppState,
+    host: &Host,
+    event: Event,
+    events: &Receiver<Event>,
+) {
+    handle_event(app_state, host, event);
+    for event in events.try_iter().take(MAX_BATCH_SIZE - 1) {
+        handle_event(app_state, host, event);
+    }
+}
+
+/// Milliseconds from a key press at `since` to `time`.
+pub fn held_ms(since: Option<&SystemTime>, time: SystemTime) -> Option<u64> {
+    let held = time.duration_since(*since?).ok()?;
+    Some(held.as_millis() as u64)
+}
+
+fn handle_event(app_state: &mut AppState, host: &Host, event: Event) {
+    // any input wakes the visualization up
+    app_state.idle.last_input = event.time;
+    if app_state.idle.idle {
+        if let Host::App {
+            app_handle,
+            toggle_menu_item,
+        } = host
+        {
+            leave_idle(app_state, app_handle, toggle_menu_item);
+        }
+    }
+
+    let mut released_after = None;
+    // track pressed keys
+    if let EventType::KeyPress(key) = event.event_type {
+        let key_name = format!("{:?}", key);
+        // If the name contains parenthesis (like "RawKey(123)", "Unknown()"), ignore it.
+        if key_name.contains('(') {
+            return;
+        }
+        // if key is already marked as pressed, it's an auto-repeat
+        if app_state.pressed_keys.contains(&key_name) {
+            if app_state.key_repeats && app_state.listening {
+                let held_ms = held_ms(app_state.key_press_times.get(&key_name), event.time);
+                app_state.push_event(
+                    InputEvent::KeyEvent {
+                        pressed: true,
+                        name: key_name,
+                        held_ms,
+                        repeat: true,
+                    },
+                    event.time,
+                );
+            }
+            return;
+        }
+        // record key as pressed
+        app_state
+            .key_press_times
+            .insert(key_name.clone(), event.time);
+        app_state.pressed_keys.push(key_name);
+        // check if toggle shortcut is pressed
+        if app_state.toggle_shortcut == app_state.pressed_keys {
+            match host {
+                Host::App {
+                    app_handle,
+                    toggle_menu_item,
+                } => app_state.toggle_listener(app_handle, toggle_menu_item),
+                Host::Headless => app_state.listening = !app_state.listening,
+            }
+
+            if !app_state.listening {
+                // emit key releases for all pressed keys
+                for key_name in &app_state.pressed_keys {
+                    app_state.stream.push(
+                        InputEvent::KeyEvent {
+                            pressed: false,
+                            name: key_name.clone(),
+                            held_ms: held_ms(app_state.key_press_times.get(key_name), event.time),
+                            repeat: false,
+                        },
+                        event.time,
+                    );
                 }
-                EventType::Wheel { delta_x, delta_y } => {
-                    Some(InputEvent::MouseWheelEvent { delta_x, delta_y })
+            }
+        } else if app_state.arrange_shortcut == app_state.pressed_keys
+            // Escape leaves the overlay where it was
+            || (app_state.interactive && key == Key::Escape)
+        {
+            if let Host::App { app_handle, .. } = host {
+                let interactive = !app_state.interactive;
+                set_interactive(app_state, app_handle, interactive);
+            }
+        }
+    } else if let EventType::KeyRelease(key) = event.event_type {
+        let key_name = format!("{:?}", key);
+        if key_name.contains('(') {
+            return;
+        }
+        // remove key from pressed keys
+        app_state.pressed_keys.retain(|k| k != &key_name);
+        let pressed_at = app_state.key_press_times.remove(&key_name);
+        released_after = held_ms(pressed_at.as_ref(), event.time);
+    }
+
+    // track mouse buttons
+    match event.event_type {
+        EventType::ButtonPress(button) => {
+            let button = map_mouse_button(button);
+            if !app_state.pressed_buttons.contains(&button) {
+                app_state.pressed_buttons.push(button);
+            }
+        }
+        EventType::ButtonRelease(button) => {
+            let button = map_mouse_button(button);
+            app_state.pressed_buttons.retain(|b| b != &button);
+        }
+        _ => {}
+    }
+
+    // emit event if listening
+    if !app_state.listening {
+        return;
+    }
+    // show clicks where they happened, not where the last coalesced move left the cursor
+    if let EventType::ButtonPress(_) | EventType::ButtonRelease(_) = event.event_type {
+        if let Some((x, y)) = app_state.mouse_coalescer.take(Instant::now()) {
+            app_state.push_event(InputEvent::MouseMoveEvent { x, y }, event.time);
+        }
+    }
+    let input_event = match event.event_type {
+        EventType::KeyPress(key) => Some(InputEvent::KeyEvent {
+            pressed: true,
+            name: format!("{:?}", key),
+            held_ms: None,
+            repeat: false,
+        }),
+        EventType::KeyRelease(key) => Some(InputEvent::KeyEvent {
+            pressed: false,
+            name: format!("{:?}", key),
+            held_ms: released_after,
+            repeat: false,
+        }),
+        EventType::ButtonPress(button) => Some(InputEvent::MouseButtonEvent {
+            pressed: true,
+            button: map_mouse_button(button),
+        }),
+        EventType::ButtonRelease(button) => Some(InputEvent::MouseButtonEvent {
+            button: map_mouse_button(button),
+            pressed: false,
+        }),
+        EventType::MouseMove { x, y } => {
+            if app_state.span_monitors {
+                route_mouse(app_state, x, y, event.time);
+            } else if app_state.follow_cursor {
+                if let Host::App { app_handle, .. } = host {
+                    follow_cursor(app_state, app_handle, x, y);
                 }
-            };
+            }
 
-            app_handle.emit("input-event", input_event).unwrap();
-        }) {
-            eprintln!("rdev listen failed: {:?}", err);
+            let (origin, scale) = app_state.mouse_origin();
+            let (logical_x, logical_y) = to_overlay(PointerSpace::native(), origin, scale, x, y);
+
+            app_state.mouse_position = (logical_x, logical_y);
+            app_state
+                .mouse_coalescer
+                .push(logical_x, logical_y, Instant::now())
+                .map(|(x, y)| InputEvent::MouseMoveEvent { x, y })
         }
-    });
+        EventType::Wheel {
+            delta_x,
+            delta_y,
+            precise_delta_x,
+            precise_delta_y,
+        } => Some(InputEvent::MouseWheelEvent {
+            delta_x,
+            delta_y,
+            precise_delta_x,
+            precise_delta_y,
+        }),
+    };
+
+    if let Some(input_event) = input_event {
+        // the watcher's poll can lag a switch to a password manager, typed
+        // text goes by the app focused right now
+        if app_state.typing.enabled && matches!(event.event_type, EventType::KeyPress(_)) {
+            app_state.foreground_app = foreground_app();
+        }
+        let app = app_state.foreground_app.clone();
+        app_state
+            .stats
+            .record(&input_event, event.time, app.as_deref());
+        let annotation = app_state.annotator.describe(&input_event, app.as_deref());
+        let sequence = app_state.sequences.feed(&input_event, event.time);
+        let text = match event.event_type {
+            EventType::KeyPress(key) => app_state.typing.feed(
+                key,
+                event.unicode.as_ref(),
+                event.platform_code,
+                &app_state.pressed_keys,
+                app.as_deref(),
+            ),
+            _ => None,
+        };
+        app_state.push_event(input_event, event.time);
+        for extra in [annotation, sequence, text].into_iter().flatten() {
+            app_state.push_event(extra, event.time);
+        }
+    }
+}
+
+/// Queues the coalesced mouse move, if one is due.
+pub fn flush_mouse(app_state: &mut AppState) {
+    if let Some((x, y)) = app_state.mouse_coalescer.flush(Instant::now()) {
+        app_state.push_event(InputEvent::MouseMoveEvent { x, y }, SystemTime::now());
+    }
+}
+
+/// Flushes coalesced mouse moves and emits everything collected as one batch,
+/// returns how long until a held back mouse move is due.
+pub fn emit_batch(app_state: &mut AppState, app_handle: &AppHandle) -> Option<Duration> {
+    flush_mouse(app_state);
+    if let Some(batch) = app_state.stream.take_batch() {
+        if let Err(e) = app_state.recorder.write(&batch) {
+            eprintln!("Recording stopped: {}", e);
+            app_state.recorder.stop();
+            notify_recording(app_handle, None);
+        }
+        // the webview notices the gap, the worker carries on
+        if let Err(e) = app_handle.emit_to("main", "input-events", batch) {
+            eprintln!("Failed to emit input events: {}", e);
+        }
+    }
+    for overlay in &mut app_state.overlays {
+        if let Some(batch) = overlay.stream.take_batch() {
+            if let Err(e) = app_handle.emit_to(overlay.label.as_str(), "input-events", batch) {
+                eprintln!("Failed to emit input events to {}: {}", overlay.label, e);
+            }
+        }
+    }
+    app_state.mouse_coalescer.due_in(Instant::now())
 }
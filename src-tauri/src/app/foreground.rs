@@ -0,0 +1,110 @@
+use std::{sync::Mutex, thread, time::Duration};
+
+use tauri::{AppHandle, Manager};
+
+use crate::app::state::AppState;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Keeps `AppState::foreground_app` current while statistics, app specific
+/// annotations, the typing ticker or activation rules need it. The typing
+/// ticker also looks it up on every key press.
+pub fn start_foreground_watcher(app_handle: AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(POLL_INTERVAL);
+
+        let state = app_handle.state::<Mutex<AppState>>();
+        let wanted = {
+            let app_state = state.lock().unwrap();
+            app_state.typing.enabled
+                || app_state.stats.enabled
+                || (app_state.activation.enabled && !app_state.activation.apps.is_empty())
+                || (app_state.annotator.enabled && app_state.annotator.db.is_app_scoped())
+        };
+        // outside the lock, it may take a round trip to the display server
+        let app = if wanted { foreground_app() } else { None };
+        state.lock().unwrap().foreground_app = app;
+    });
+}
+
+/// Name of the app owning the focused window, e.g. "firefox" or "Code".
+#[cfg(target_os = "linux")]
+pub fn foreground_app() -> Option<String> {
+    use std::cell::RefCell;
+
+    thread_local! {
+        // one X connection per thread asking instead of one per call
+        static ACTIVE_WINDOW: RefCell<Option<rdev::ActiveWindow>> = const { RefCell::new(None) };
+    }
+    ACTIVE_WINDOW.with(|active_window| {
+        let mut active_window = active_window.borrow_mut();
+        if active_window.is_none() {
+            *active_window = rdev::ActiveWindow::new().ok();
+        }
+        active_window.as_ref()?.class()
+    })
+}
+
+#[cfg(target_os = "macos")]
+pub fn foreground_app() -> Option<String> {
+    use cocoa::base::{id, nil};
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::{ffi::CStr, os::raw::c_char};
+
+    unsafe {
+        let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+        let app: id = msg_send![workspace, frontmostApplication];
+        if app == nil {
+            return None;
+        }
+        let name: id = msg_send![app, localizedName];
+        if name == nil {
+            return None;
+        }
+        let name: *const c_char = msg_send![name, UTF8String];
+        (!name.is_null()).then(|| CStr::from_ptr(name).to_string_lossy().into_owned())
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn foreground_app() -> Option<String> {
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+
+    unsafe {
+        let mut process_id = 0;
+        GetWindowThreadProcessId(GetForegroundWindow(), Some(&mut process_id));
+        if process_id == 0 {
+            return None;
+        }
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id).ok()?;
+        let mut buffer = [0u16; 1024];
+        let mut len = buffer.len() as u32;
+        let ok = QueryFullProcessImageNameW(
+            process,
+            PROCESS_NAME_WIN32,
+            PWSTR(buffer.as_mut_ptr()),
+            &mut len,
+        )
+        .as_bool();
+        CloseHandle(process);
+        if !ok {
+            return None;
+        }
+        // "C:\...\Code.exe" -> "Code"
+        let path = String::from_utf16_lossy(&buffer[..len as usize]);
+        std::path::Path::new(&path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+pub fn foreground_app() -> Option<String> {
+    None
+}
@@ -0,0 +1,196 @@
+use std::{
+    io::{self, Write},
+    sync::{mpsc::RecvTimeoutError, Arc},
+    time::Instant,
+};
+
+use crate::app::event::{
+    flush_mouse, handle_events, recv_until, spawn_listen_thread, Host, InputEvent,
+};
+use crate::app::listener::ListenerStatus;
+use crate::app::queue::{event_queue, QueueCounters};
+use crate::app::state::AppState;
+
+const MODIFIERS: &[&str] = &[
+    "ShiftLeft",
+    "ShiftRight",
+    "ControlLeft",
+    "ControlRight",
+    "Alt",
+    "AltGr",
+    "MetaLeft",
+    "MetaRight",
+    "Function",
+];
+
+/// Runs the input pipeline without any window or tray, printing to stdout:
+/// combos as text, or every event as a JSON line.
+pub fn run_headless(json: bool) {
+    let counters = Arc::new(QueueCounters::default());
+    let (sender, events) = event_queue(counters);
+    spawn_listen_thread(sender, |status| {
+        if let ListenerStatus::Failed {
+            diagnostic,
+            retry_in_ms,
+            ..
+        } = status
+        {
+            eprintln!(
+                "{} ({}), retrying in {}ms",
+                diagnostic.message, diagnostic.hint, retry_in_ms
+            );
+        }
+    });
+
+    let mut app_state = AppState {
+        listening: true,
+        ..Default::default()
+    };
+    let mut combos = ComboFormatter::default();
+    let host = Host::Headless;
+
+    loop {
+        let flush_in = app_state.mouse_coalescer.due_in(Instant::now());
+        match recv_until(&events, flush_in) {
+            Ok(event) => handle_events(&mut app_state, &host, event, &events),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        flush_mouse(&mut app_state);
+
+        let Some(batch) = app_state.stream.take_batch() else {
+            continue;
+        };
+        let mut stdout = io::stdout().lock();
+        for event in batch {
+            let line = if json {
+                serde_json::to_string(&event).ok()
+            } else {
+                combos.format(&event.event)
+            };
+            if let Some(line) = line {
+                // stop quietly when the reading end goes away, e.g. `| head`
+                if writeln!(stdout, "{}", line).is_err() {
+                    return;
+                }
+            }
+        }
+        if stdout.flush().is_err() {
+            return;
+        }
+    }
+}
+
+/// Turns key events into one line per combo, e.g. "ControlLeft+ShiftLeft+KeyP".
+#[derive(Default)]
+pub struct ComboFormatter {
+    held: Vec<String>,
+    // modifiers went down with nothing else pressed yet
+    lone_modifiers: bool,
+}
+
+impl ComboFormatter {
+    pub fn format(&mut self, event: &InputEvent) -> Option<String> {
+        match event {
+            InputEvent::KeyEvent {
+                pressed: true,
+                name,
+                ..
+            } => {
+                if MODIFIERS.contains(&name.as_str()) {
+                    if !self.held.contains(name) {
+                        self.held.push(name.clone());
+                    }
+                    self.lone_modifiers = true;
+                    return None;
+                }
+                self.lone_modifiers = false;
+                Some(self.combo(name))
+            }
+            InputEvent::KeyEvent {
+                pressed: false,
+                name,
+                ..
+            } => {
+                // a modifier tapped on its own is a combo of its own
+                let line =
+                    (self.lone_modifiers && self.held.contains(name)).then(|| self.held.join("+"));
+                self.lone_modifiers = false;
+                self.held.retain(|k| k != name);
+                line
+            }
+            InputEvent::MouseButtonEvent {
+                pressed: true,
+                button,
+            } => {
+                self.lone_modifiers = false;
+                Some(self.combo(&format!("{:?}Click", button)))
+            }
+            InputEvent::MouseWheelEvent {
+                precise_delta_y, ..
+            } if *precise_delta_y != 0.0 => {
+                let direction = if *precise_delta_y > 0.0 { "Up" } else { "Down" };
+                Some(self.combo(&format!("Scroll{}", direction)))
+            }
+            _ => None,
+        }
+    }
+
+    fn combo(&self, name: &str) -> String {
+        self.held
+            .iter()
+            .map(String::as_str)
+            .chain(std::iter::once(name))
+            .collect::<Vec<_>>()
+            .join("+")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::event::MouseButton;
+
+    fn key(name: &str, pressed: bool) -> InputEvent {
+        InputEvent::KeyEvent {
+            pressed,
+            name: name.to_string(),
+            held_ms: None,
+            repeat: false,
+        }
+    }
+
+    #[test]
+    fn groups_combos() {
+        let mut combos = ComboFormatter::default();
+        let events = [
+            key("ControlLeft", true),
+            key("ShiftLeft", true),
+            key("KeyP", true),
+            key("KeyP", false),
+            key("ShiftLeft", false),
+            key("ControlLeft", false),
+            key("KeyA", true),
+            key("KeyA", false),
+            key("MetaLeft", true),
+            key("MetaLeft", false),
+            key("ShiftLeft", true),
+            InputEvent::MouseButtonEvent {
+                pressed: true,
+                button: MouseButton::Left,
+            },
+            key("ShiftLeft", false),
+        ];
+        let lines: Vec<String> = events.iter().filter_map(|e| combos.format(e)).collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "ControlLeft+ShiftLeft+KeyP",
+                "KeyA",
+                "MetaLeft",
+                "ShiftLeft+LeftClick"
+            ]
+        );
+    }
+}
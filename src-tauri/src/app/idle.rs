@@ -0,0 +1,127 @@
+use std::{
+    sync::Mutex,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use tauri::{menu::MenuItem, AppHandle, Emitter, Manager, Wry};
+
+use crate::app::state::AppState;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Time since the last input, pausing the visualization after a while.
+pub struct IdleTracker {
+    /// `None` never goes idle
+    pub timeout: Option<Duration>,
+    /// hide the overlays too, sparing the compositor
+    pub hide_windows: bool,
+    pub last_input: SystemTime,
+    pub idle: bool,
+    /// listening was on when going idle, so waking up turns it back on
+    paused: bool,
+    hidden: bool,
+}
+
+impl Default for IdleTracker {
+    fn default() -> Self {
+        Self {
+            timeout: None,
+            hide_windows: false,
+            last_input: SystemTime::now(),
+            idle: false,
+            paused: false,
+            hidden: false,
+        }
+    }
+}
+
+impl IdleTracker {
+    /// Whether the last input was longer than `timeout` ago.
+    pub fn is_due(&self, now: SystemTime) -> bool {
+        !self.idle
+            && self.timeout.is_some_and(|timeout| {
+                now.duration_since(self.last_input)
+                    .is_ok_and(|quiet| quiet >= timeout)
+            })
+    }
+}
+
+/// Pauses the visualization until the next input.
+pub fn enter_idle(app_state: &mut AppState, app: &AppHandle, toggle: &MenuItem<Wry>) {
+    println!("💤 Idle");
+    app_state.idle.idle = true;
+    app_state.idle.paused = app_state.listening;
+    if app_state.listening {
+        app_state.toggle_listener(app, toggle);
+    }
+    if app_state.idle.hide_windows {
+        for (label, window) in app.webview_windows() {
+            if label != "settings" {
+                let _ = window.hide();
+            }
+        }
+        app_state.idle.hidden = true;
+    }
+    if let Err(e) = app.emit("idle-changed", true) {
+        eprintln!("Failed to emit idle change: {}", e);
+    }
+}
+
+/// Picks up where `enter_idle` left off.
+pub fn leave_idle(app_state: &mut AppState, app: &AppHandle, toggle: &MenuItem<Wry>) {
+    println!("👋 Active");
+    app_state.idle.idle = false;
+    if app_state.idle.hidden {
+        for (label, window) in app.webview_windows() {
+            if label != "settings" {
+                let _ = window.show();
+            }
+        }
+        app_state.idle.hidden = false;
+    }
+    // unless it was stopped by hand in the meantime
+    if app_state.idle.paused && !app_state.listening {
+        app_state.toggle_listener(app, toggle);
+    }
+    app_state.idle.paused = false;
+    if let Err(e) = app.emit("idle-changed", false) {
+        eprintln!("Failed to emit idle change: {}", e);
+    }
+}
+
+/// Checks now and then whether input has gone quiet for long enough.
+pub fn start_idle_watcher(app_handle: AppHandle, toggle_item: MenuItem<Wry>) {
+    thread::spawn(move || loop {
+        thread::sleep(POLL_INTERVAL);
+
+        // nothing under the lock waits on the main thread, the tray update is posted
+        let state = app_handle.state::<Mutex<AppState>>();
+        let mut app_state = state.lock().unwrap();
+        if app_state.idle.is_due(SystemTime::now()) {
+            enter_idle(&mut app_state, &app_handle, &toggle_item);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn goes_idle_after_timeout() {
+        let start = SystemTime::now();
+        let mut idle = IdleTracker {
+            last_input: start,
+            ..Default::default()
+        };
+        assert!(!idle.is_due(start + Duration::from_secs(3600)));
+
+        idle.timeout = Some(Duration::from_secs(60));
+        assert!(!idle.is_due(start + Duration::from_secs(59)));
+        assert!(idle.is_due(start + Duration::from_secs(60)));
+
+        idle.idle = true;
+        assert!(!idle.is_due(start + Duration::from_secs(120)));
+    }
+}
@@ -0,0 +1,121 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use tauri::{AppHandle, Manager};
+
+use crate::app::event::emit_batch;
+use crate::app::state::AppState;
+
+const RECONCILE_INTERVAL: Duration = Duration::from_secs(1);
+// the OS may not have caught up with a press we just received
+const PRESS_GRACE: Duration = Duration::from_millis(250);
+
+/// Periodically compares the tracked pressed keys with the keyboard state
+/// reported by the OS and releases keys whose release event was lost, e.g.
+/// during a lock screen or a focus change.
+pub fn start_key_reconciler(app_handle: AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(RECONCILE_INTERVAL);
+
+        let state = app_handle.state::<Mutex<AppState>>();
+        if state.lock().unwrap().pressed_keys.is_empty() {
+            continue;
+        }
+
+        // query without holding the lock, this can be slow on some platforms
+        let actual: Option<Vec<String>> = match rdev::pressed_keys() {
+            Ok(keys) => Some(keys.iter().map(|key| format!("{:?}", key)).collect()),
+            Err(err) => {
+                eprintln!("Failed to query keyboard state: {:?}", err);
+                None
+            }
+        };
+
+        let mut app_state = state.lock().unwrap();
+        let now = SystemTime::now();
+        let stale = stale_keys(
+            &app_state.pressed_keys,
+            &app_state.key_press_times,
+            actual.as_deref(),
+            app_state.stuck_key_timeout,
+            now,
+        );
+        if stale.is_empty() {
+            continue;
+        }
+        for key_name in stale {
+            println!("Releasing stuck key {}", key_name);
+            app_state.release_key(&key_name, now);
+        }
+        // the worker only wakes up for input
+        emit_batch(&mut app_state, &app_handle);
+    });
+}
+
+/// Keys tracked as pressed that the OS reports released, or that were held
+/// longer than `timeout`.
+pub fn stale_keys(
+    pressed: &[String],
+    press_times: &HashMap<String, SystemTime>,
+    actual: Option<&[String]>,
+    timeout: Option<Duration>,
+    now: SystemTime,
+) -> Vec<String> {
+    pressed
+        .iter()
+        .filter(|key_name| {
+            let held = press_times
+                .get(*key_name)
+                .and_then(|time| now.duration_since(*time).ok())
+                .unwrap_or_default();
+            let released = actual.is_some_and(|actual| !actual.contains(*key_name));
+
+            (released && held >= PRESS_GRACE) || timeout.is_some_and(|timeout| held >= timeout)
+        })
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pressed(
+        keys: &[(&str, u64)],
+        now: SystemTime,
+    ) -> (Vec<String>, HashMap<String, SystemTime>) {
+        let names = keys.iter().map(|(name, _)| name.to_string()).collect();
+        let times = keys
+            .iter()
+            .map(|(name, ago)| (name.to_string(), now - Duration::from_millis(*ago)))
+            .collect();
+        (names, times)
+    }
+
+    #[test]
+    fn releases_keys_the_os_reports_up() {
+        let now = SystemTime::now();
+        let (names, times) = pressed(&[("ShiftLeft", 5_000), ("KeyA", 5_000), ("KeyB", 10)], now);
+        let actual = vec!["ShiftLeft".to_string()];
+
+        let stale = stale_keys(&names, &times, Some(&actual), None, now);
+        // KeyB was just pressed, the OS may not know yet
+        assert_eq!(stale, vec!["KeyA".to_string()]);
+    }
+
+    #[test]
+    fn releases_keys_held_too_long() {
+        let now = SystemTime::now();
+        let (names, times) = pressed(&[("ShiftLeft", 90_000), ("KeyA", 5_000)], now);
+
+        let stale = stale_keys(&names, &times, None, Some(Duration::from_secs(60)), now);
+        assert_eq!(stale, vec!["ShiftLeft".to_string()]);
+
+        let stale = stale_keys(&names, &times, None, None, now);
+        assert!(stale.is_empty());
+    }
+}
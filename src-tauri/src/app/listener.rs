@@ -0,0 +1,126 @@
+use std::{sync::Mutex, time::Duration};
+
+use rdev::ListenError;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::app::state::AppState;
+
+/// What went wrong with the global input listener, in words a user can act on.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ListenerDiagnostic {
+    pub code: &'static str,
+    pub message: &'static str,
+    pub hint: &'static str,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(tag = "state", rename_all = "lowercase")]
+pub enum ListenerStatus {
+    #[default]
+    Starting,
+    Running,
+    Failed {
+        diagnostic: ListenerDiagnostic,
+        attempt: u32,
+        retry_in_ms: u64,
+    },
+}
+
+impl ListenerStatus {
+    pub fn is_failed(&self) -> bool {
+        matches!(self, ListenerStatus::Failed { .. })
+    }
+}
+
+pub fn diagnose_listen_error(err: &ListenError) -> ListenerDiagnostic {
+    match err {
+        ListenError::MissingDisplayError => ListenerDiagnostic {
+            code: "no-display",
+            message: "Could not connect to the X display",
+            hint: "Keyviz needs an X11 session, or XWayland with DISPLAY set",
+        },
+        ListenError::XRecordExtensionError
+        | ListenError::RecordContextError
+        | ListenError::RecordContextEnablingError => ListenerDiagnostic {
+            code: "xrecord-missing",
+            message: "The X server's RECORD extension is unavailable",
+            hint: "Enable the RECORD extension in your X server configuration",
+        },
+        ListenError::KeyboardError => ListenerDiagnostic {
+            code: "keyboard",
+            message: "Could not read the keyboard layout",
+            hint: "Check that an X keyboard map is loaded",
+        },
+        ListenError::EventTapError | ListenError::LoopSourceError => ListenerDiagnostic {
+            code: "accessibility",
+            message: "Could not tap into input events",
+            hint: "Grant Keyviz access under System Settings > Privacy & Security > Accessibility",
+        },
+        ListenError::KeyHookError(_) | ListenError::MouseHookError(_) => ListenerDiagnostic {
+            code: "hook-failed",
+            message: "Could not install the keyboard or mouse hook",
+            hint: "Another program may be blocking global hooks, try restarting Keyviz",
+        },
+        _ => ListenerDiagnostic {
+            code: "unknown",
+            message: "The input listener stopped unexpectedly",
+            hint: "Try restarting Keyviz",
+        },
+    }
+}
+
+/// Backoff between restart attempts: quick retries for transient failures,
+/// then slowing down for ones that need the user to fix something.
+pub fn retry_delay(attempt: u32) -> Duration {
+    match attempt {
+        0..=3 => Duration::from_millis(500),
+        4..=9 => Duration::from_secs(attempt as u64),
+        _ => Duration::from_secs(30),
+    }
+}
+
+pub fn set_listener_status(app_handle: &AppHandle, status: ListenerStatus) {
+    let state = app_handle.state::<Mutex<AppState>>();
+    let mut app_state = state.lock().unwrap();
+    apply_listener_status(&mut app_state, app_handle, status);
+}
+
+/// Records the listener status, reflects it in the tray and tells the
+/// settings window. The tray update is only posted to the main thread, the
+/// listen thread and the worker call this with the state locked.
+pub fn apply_listener_status(
+    app_state: &mut AppState,
+    app_handle: &AppHandle,
+    status: ListenerStatus,
+) {
+    if app_state.listener_status == status {
+        return;
+    }
+    app_state.listener_status = status.clone();
+    app_state.show_tray(app_handle, None);
+
+    if let Err(e) = app_handle.emit_to("settings", "listener-status", status) {
+        eprintln!("Failed to emit listener status: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_errors_to_diagnostics() {
+        let code = |err| diagnose_listen_error(&err).code;
+        assert_eq!(code(ListenError::MissingDisplayError), "no-display");
+        assert_eq!(code(ListenError::XRecordExtensionError), "xrecord-missing");
+        assert_eq!(code(ListenError::KeyHookError(5)), "hook-failed");
+    }
+
+    #[test]
+    fn backs_off() {
+        assert_eq!(retry_delay(1), Duration::from_millis(500));
+        assert_eq!(retry_delay(5), Duration::from_secs(5));
+        assert_eq!(retry_delay(100), Duration::from_secs(30));
+    }
+}
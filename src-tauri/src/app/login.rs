@@ -0,0 +1,334 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Flag the login entry passes to start with visualization off.
+pub const START_PAUSED_FLAG: &str = "--stop";
+
+/// Whether keyviz starts with the session, and how.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchAtLogin {
+    pub enabled: bool,
+    pub start_paused: bool,
+}
+
+/// Path the login entry should run, the AppImage rather than its mount.
+fn executable() -> Result<PathBuf, String> {
+    if let Some(appimage) = std::env::var_os("APPIMAGE") {
+        return Ok(PathBuf::from(appimage));
+    }
+    std::env::current_exe().map_err(|e| e.to_string())
+}
+
+pub fn launch_at_login() -> LaunchAtLogin {
+    platform::read().unwrap_or_default()
+}
+
+pub fn set_launch_at_login(setting: LaunchAtLogin) -> Result<(), String> {
+    if setting.enabled {
+        platform::write(&executable()?, setting.start_paused)
+    } else {
+        platform::remove()
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::*;
+    use std::fs;
+
+    const DESKTOP_FILE: &str = "keyviz.desktop";
+
+    /// `$XDG_CONFIG_HOME/autostart`, `~/.config/autostart` by default.
+    pub fn autostart_dir(
+        config_home: Option<std::ffi::OsString>,
+        home: Option<std::ffi::OsString>,
+    ) -> Option<PathBuf> {
+        let config_home = config_home
+            .map(PathBuf::from)
+            // relative paths are invalid per the base directory spec
+            .filter(|path| path.is_absolute())
+            .or_else(|| Some(PathBuf::from(home?).join(".config")))?;
+        Some(config_home.join("autostart"))
+    }
+
+    fn user_autostart_dir() -> Result<PathBuf, String> {
+        autostart_dir(
+            std::env::var_os("XDG_CONFIG_HOME"),
+            std::env::var_os("HOME"),
+        )
+        .ok_or_else(|| "neither XDG_CONFIG_HOME nor HOME is set".to_string())
+    }
+
+    /// Quotes an argument for the `Exec` key of a desktop entry.
+    fn quote_exec_arg(arg: &str) -> String {
+        if !arg.contains(|c: char| c.is_whitespace() || "\"'\\><~|&;$*?#()`".contains(c)) {
+            return arg.to_string();
+        }
+        let mut quoted = String::from("\"");
+        for c in arg.chars() {
+            if matches!(c, '"' | '`' | '$' | '\\') {
+                quoted.push('\\');
+            }
+            quoted.push(c);
+        }
+        quoted.push('"');
+        // the key itself is a string value, where backslashes escape again
+        quoted.replace('\\', "\\\\")
+    }
+
+    pub fn desktop_entry(executable: &Path, start_paused: bool) -> String {
+        let mut exec = quote_exec_arg(&executable.to_string_lossy());
+        if start_paused {
+            exec.push(' ');
+            exec.push_str(START_PAUSED_FLAG);
+        }
+        format!(
+            "[Desktop Entry]\n\
+             Type=Application\n\
+             Name=Keyviz\n\
+             Comment=Visualize keystrokes and mouse actions\n\
+             Exec={}\n\
+             Icon=keyviz\n\
+             Terminal=false\n\
+             X-GNOME-Autostart-enabled=true\n",
+            exec
+        )
+    }
+
+    pub fn read() -> Option<LaunchAtLogin> {
+        read_entry(&user_autostart_dir().ok()?)
+    }
+
+    pub fn write(executable: &Path, start_paused: bool) -> Result<(), String> {
+        write_entry(&user_autostart_dir()?, executable, start_paused)
+    }
+
+    pub fn remove() -> Result<(), String> {
+        remove_entry(&user_autostart_dir()?)
+    }
+
+    pub fn read_entry(autostart_dir: &Path) -> Option<LaunchAtLogin> {
+        let content = fs::read_to_string(autostart_dir.join(DESKTOP_FILE)).ok()?;
+        let exec = content
+            .lines()
+            .find_map(|line| line.strip_prefix("Exec="))?;
+        Some(LaunchAtLogin {
+            enabled: true,
+            start_paused: exec.split_whitespace().any(|arg| arg == START_PAUSED_FLAG),
+        })
+    }
+
+    pub fn write_entry(
+        autostart_dir: &Path,
+        executable: &Path,
+        start_paused: bool,
+    ) -> Result<(), String> {
+        fs::create_dir_all(autostart_dir).map_err(|e| e.to_string())?;
+        fs::write(
+            autostart_dir.join(DESKTOP_FILE),
+            desktop_entry(executable, start_paused),
+        )
+        .map_err(|e| e.to_string())
+    }
+
+    pub fn remove_entry(autostart_dir: &Path) -> Result<(), String> {
+        match fs::remove_file(autostart_dir.join(DESKTOP_FILE)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+    use std::fs;
+
+    const LABEL: &str = "org.keyviz";
+
+    fn plist_path() -> Result<PathBuf, String> {
+        let home = std::env::var_os("HOME").ok_or("HOME is not set")?;
+        Ok(PathBuf::from(home)
+            .join("Library/LaunchAgents")
+            .join(format!("{}.plist", LABEL)))
+    }
+
+    fn escape(value: &str) -> String {
+        value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    }
+
+    pub fn read() -> Option<LaunchAtLogin> {
+        let content = fs::read_to_string(plist_path().ok()?).ok()?;
+        Some(LaunchAtLogin {
+            enabled: true,
+            start_paused: content.contains(&format!("<string>{}</string>", START_PAUSED_FLAG)),
+        })
+    }
+
+    pub fn write(executable: &Path, start_paused: bool) -> Result<(), String> {
+        let mut args = format!("<string>{}</string>", escape(&executable.to_string_lossy()));
+        if start_paused {
+            args.push_str(&format!("<string>{}</string>", START_PAUSED_FLAG));
+        }
+        let plist = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key><string>{}</string>
+    <key>ProgramArguments</key><array>{}</array>
+    <key>RunAtLoad</key><true/>
+</dict>
+</plist>
+"#,
+            LABEL, args
+        );
+        let path = plist_path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        fs::write(path, plist).map_err(|e| e.to_string())
+    }
+
+    pub fn remove() -> Result<(), String> {
+        match fs::remove_file(plist_path()?) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+
+    const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+    const VALUE: &str = "Keyviz";
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    fn reg(args: &[&str]) -> Result<String, String> {
+        let output = Command::new("reg")
+            .args(args)
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .map_err(|e| e.to_string())?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    pub fn read() -> Option<LaunchAtLogin> {
+        let output = reg(&["query", RUN_KEY, "/v", VALUE]).ok()?;
+        Some(LaunchAtLogin {
+            enabled: true,
+            start_paused: output.contains(START_PAUSED_FLAG),
+        })
+    }
+
+    pub fn write(executable: &Path, start_paused: bool) -> Result<(), String> {
+        let mut command = format!("\"{}\"", executable.display());
+        if start_paused {
+            command.push(' ');
+            command.push_str(START_PAUSED_FLAG);
+        }
+        reg(&[
+            "add", RUN_KEY, "/v", VALUE, "/t", "REG_SZ", "/d", &command, "/f",
+        ])
+        .map(|_| ())
+    }
+
+    pub fn remove() -> Result<(), String> {
+        if read().is_none() {
+            return Ok(());
+        }
+        reg(&["delete", RUN_KEY, "/v", VALUE, "/f"]).map(|_| ())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+mod platform {
+    use super::*;
+
+    pub fn read() -> Option<LaunchAtLogin> {
+        None
+    }
+
+    pub fn write(_executable: &Path, _start_paused: bool) -> Result<(), String> {
+        Err("launch at login is not supported on this platform".to_string())
+    }
+
+    pub fn remove() -> Result<(), String> {
+        Ok(())
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::platform::*;
+    use super::*;
+
+    #[test]
+    fn finds_autostart_dir() {
+        assert_eq!(
+            autostart_dir(Some("/xdg".into()), Some("/home/me".into())),
+            Some(PathBuf::from("/xdg/autostart"))
+        );
+        assert_eq!(
+            autostart_dir(Some("relative".into()), Some("/home/me".into())),
+            Some(PathBuf::from("/home/me/.config/autostart"))
+        );
+        assert_eq!(autostart_dir(None, None), None);
+    }
+
+    #[test]
+    fn writes_xdg_autostart_entry() {
+        let dir =
+            std::env::temp_dir().join(format!("keyviz-login-{}/autostart", std::process::id()));
+        let entry = dir.join("keyviz.desktop");
+        let executable = Path::new("/usr/bin/keyviz");
+        assert_eq!(read_entry(&dir), None);
+
+        write_entry(&dir, executable, true).unwrap();
+        assert!(entry.exists());
+        assert_eq!(
+            read_entry(&dir),
+            Some(LaunchAtLogin {
+                enabled: true,
+                start_paused: true,
+            })
+        );
+
+        write_entry(&dir, executable, false).unwrap();
+        assert_eq!(
+            read_entry(&dir),
+            Some(LaunchAtLogin {
+                enabled: true,
+                start_paused: false,
+            })
+        );
+
+        remove_entry(&dir).unwrap();
+        assert!(!entry.exists());
+        assert_eq!(read_entry(&dir), None);
+        // removing twice is fine
+        remove_entry(&dir).unwrap();
+
+        std::fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn quotes_exec_paths() {
+        let entry = desktop_entry(Path::new("/opt/My Apps/keyviz"), true);
+        assert!(entry.contains("Exec=\"/opt/My Apps/keyviz\" --stop\n"));
+        let entry = desktop_entry(Path::new("/usr/bin/keyviz"), false);
+        assert!(entry.contains("Exec=/usr/bin/keyviz\n"));
+    }
+}
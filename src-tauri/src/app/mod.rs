@@ -1,4 +1,29 @@
+pub mod activation;
+pub mod annotations;
+pub mod cli;
+pub mod coalesce;
 pub mod commands;
+pub mod control;
+pub mod coords;
+pub mod diagnose;
 pub mod event;
+pub mod foreground;
+pub mod headless;
+pub mod idle;
+pub mod keystate;
+pub mod listener;
+pub mod login;
+pub mod monitor;
+pub mod overlay;
+pub mod profile;
+pub mod queue;
+pub mod recording;
+pub mod sequence;
 pub mod state;
+pub mod stats;
+pub mod store;
+pub mod stream;
+pub mod theme;
+pub mod tray;
+pub mod typing;
 pub mod window;
@@ -0,0 +1,315 @@
+use std::{sync::Mutex, thread, time::Duration};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Monitor, PhysicalPosition, PhysicalSize, WebviewWindow};
+
+use crate::app::coords::{monitor_bounds, PointerSpace};
+use crate::app::overlay::sync_span_overlays;
+use crate::app::state::AppState;
+
+// how far the pointer has to travel past the edge before the overlay follows it
+const FOLLOW_HYSTERESIS: f64 = 48.0;
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MonitorInfo {
+    pub name: Option<String>,
+    pub position: (i32, i32),
+    pub size: (u32, u32),
+    pub scale: f64,
+}
+
+impl From<&Monitor> for MonitorInfo {
+    fn from(monitor: &Monitor) -> Self {
+        Self {
+            name: monitor.name().cloned(),
+            position: (monitor.position().x, monitor.position().y),
+            size: (monitor.size().width, monitor.size().height),
+            scale: monitor.scale_factor(),
+        }
+    }
+}
+
+impl MonitorInfo {
+    /// Whether a pointer position, as reported by rdev, lies on this monitor
+    /// or within `margin` of it.
+    fn contains(&self, x: f64, y: f64, margin: f64) -> bool {
+        monitor_bounds(PointerSpace::native(), self).contains(x, y, margin)
+    }
+}
+
+pub fn list_monitors(window: &WebviewWindow) -> Vec<MonitorInfo> {
+    window
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(MonitorInfo::from)
+        .collect()
+}
+
+/// Picks the monitor under the pointer, sticking with `current` until the
+/// pointer is well past its edge so the overlay doesn't flap between screens.
+pub fn monitor_at<'a>(
+    monitors: &'a [MonitorInfo],
+    current: Option<&MonitorInfo>,
+    x: f64,
+    y: f64,
+) -> Option<&'a MonitorInfo> {
+    if let Some(current) = current {
+        if current.contains(x, y, FOLLOW_HYSTERESIS) {
+            return monitors.iter().find(|m| *m == current);
+        }
+    }
+    monitors.iter().find(|m| m.contains(x, y, 0.0))
+}
+
+/// Picks the monitor named `preferred`, falling back to the primary monitor
+/// and then to any monitor at all.
+pub fn resolve_monitor<'a>(
+    monitors: &'a [MonitorInfo],
+    preferred: Option<&str>,
+    primary: Option<&MonitorInfo>,
+) -> Option<&'a MonitorInfo> {
+    let by_name = |name: Option<&str>| {
+        name.and_then(|name| monitors.iter().find(|m| m.name.as_deref() == Some(name)))
+    };
+    by_name(preferred)
+        .or_else(|| by_name(primary.and_then(|p| p.name.as_deref())))
+        .or_else(|| monitors.first())
+}
+
+/// Moves and resizes the overlay to cover `monitor`, and remembers it for
+/// mouse coordinate conversion.
+pub fn move_to_monitor(app_state: &mut AppState, window: &WebviewWindow, monitor: &MonitorInfo) {
+    remember_monitor(app_state, monitor);
+    place_window(window, monitor);
+}
+
+/// Remembers `monitor` as the one the overlay covers.
+pub fn remember_monitor(app_state: &mut AppState, monitor: &MonitorInfo) {
+    app_state.monitor_name = monitor.name.clone();
+    app_state.monitor_scale = monitor.scale;
+    app_state.monitor_position = monitor.position;
+}
+
+fn is_current_monitor(app_state: &AppState, monitor: &MonitorInfo) -> bool {
+    app_state.monitor_name == monitor.name
+        && app_state.monitor_position == monitor.position
+        && app_state.monitor_scale == monitor.scale
+}
+
+/// Catches up with the window manager moving the overlay to another monitor,
+/// or the monitor's scale changing under it.
+///
+/// Runs on its own thread, the main thread answers the monitor query.
+pub fn track_main_window_monitor(app_handle: &AppHandle) {
+    let app_handle = app_handle.clone();
+    thread::spawn(move || {
+        let Some(window) = app_handle.get_webview_window("main") else {
+            return;
+        };
+        let Some(monitor) = window.current_monitor().ok().flatten() else {
+            return;
+        };
+        let monitor = MonitorInfo::from(&monitor);
+
+        let state = app_handle.state::<Mutex<AppState>>();
+        let mut app_state = state.lock().unwrap();
+        if is_current_monitor(&app_state, &monitor) {
+            return;
+        }
+        remember_monitor(&mut app_state, &monitor);
+        // the monitor the main window left needs an overlay now
+        if app_state.span_monitors {
+            drop(app_state);
+            sync_span_overlays(&app_handle);
+        }
+    });
+}
+
+/// Pins the main overlay to the monitor named `monitor_name`, the choice
+/// made in the settings window.
+pub fn select_main_window_monitor(
+    app_handle: &AppHandle,
+    monitor_name: &str,
+) -> Result<(), String> {
+    let window = app_handle
+        .get_webview_window("main")
+        .ok_or("main window is not open")?;
+    // before locking, the main thread answers this and may be waiting on the lock
+    let monitors = list_monitors(&window);
+
+    let state = app_handle.state::<Mutex<AppState>>();
+    let mut app_state = state.lock().unwrap();
+    app_state.preferred_monitor = Some(monitor_name.to_string());
+
+    if app_state.monitor_name.as_deref() == Some(monitor_name) {
+        return Ok(());
+    }
+    let monitor = monitors
+        .iter()
+        .find(|m| m.name.as_deref() == Some(monitor_name))
+        .cloned()
+        .ok_or_else(|| format!("no monitor named {:?}", monitor_name))?;
+    app_state.monitors = monitors;
+
+    move_to_monitor(&mut app_state, &window, &monitor);
+    // the monitor the main window left needs an overlay now
+    if app_state.span_monitors {
+        drop(app_state);
+        sync_span_overlays(app_handle);
+    }
+    Ok(())
+}
+
+/// Moves and resizes a window to cover `monitor`.
+pub fn place_window(window: &WebviewWindow, monitor: &MonitorInfo) {
+    window
+        .set_position(PhysicalPosition {
+            x: monitor.position.0,
+            y: monitor.position.1,
+        })
+        .unwrap_or(());
+    window
+        .set_size(PhysicalSize {
+            width: monitor.size.0,
+            height: monitor.size.1,
+        })
+        .unwrap_or(());
+}
+
+/// Jumps the overlay to the monitor under the pointer, when it changed.
+pub fn follow_cursor(app_state: &mut AppState, app_handle: &AppHandle, x: f64, y: f64) {
+    let current = app_state
+        .monitors
+        .iter()
+        .find(|m| m.name == app_state.monitor_name && m.position == app_state.monitor_position);
+    let target = match monitor_at(&app_state.monitors, current, x, y) {
+        Some(target) if Some(target) != current => target.clone(),
+        _ => return,
+    };
+
+    if let Some(window) = app_handle.get_webview_window("main") {
+        move_to_monitor(app_state, &window, &target);
+        if let Err(e) = app_handle.emit_to("main", "monitor-changed", target.name) {
+            eprintln!("Failed to emit monitor change: {}", e);
+        }
+    }
+}
+
+/// Polls the monitor layout and puts the overlay back in place when monitors
+/// are plugged, unplugged or change resolution.
+pub fn start_monitor_watcher(app_handle: AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(WATCH_INTERVAL);
+
+        let Some(window) = app_handle.get_webview_window("main") else {
+            continue;
+        };
+        // query before locking, the main thread answers these and may be
+        // waiting on the lock itself
+        let monitors = list_monitors(&window);
+        // the list can come back empty mid reconfiguration
+        if monitors.is_empty() {
+            continue;
+        }
+        let primary = window
+            .primary_monitor()
+            .ok()
+            .flatten()
+            .map(|m| MonitorInfo::from(&m));
+
+        let state = app_handle.state::<Mutex<AppState>>();
+        let mut app_state = state.lock().unwrap();
+        if monitors == app_state.monitors {
+            continue;
+        }
+        let initial = app_state.monitors.is_empty();
+        let previous = app_state
+            .monitors
+            .iter()
+            .find(|m| m.name == app_state.monitor_name && m.position == app_state.monitor_position)
+            .cloned();
+        app_state.monitors = monitors;
+        if initial {
+            continue;
+        }
+        println!("🖥️ Monitor configuration changed");
+
+        // in follow mode there's no preference, stay where the pointer was
+        let preferred = if app_state.follow_cursor {
+            app_state.monitor_name.clone()
+        } else {
+            app_state.preferred_monitor.clone()
+        };
+        let target =
+            resolve_monitor(&app_state.monitors, preferred.as_deref(), primary.as_ref()).cloned();
+        if let Some(target) = target {
+            if previous.as_ref() != Some(&target) {
+                move_to_monitor(&mut app_state, &window, &target);
+            }
+        }
+        let span_monitors = app_state.span_monitors;
+        let monitors = app_state.monitors.clone();
+        drop(app_state);
+        if span_monitors {
+            sync_span_overlays(&app_handle);
+        }
+
+        app_handle.emit("monitors-changed", &monitors).unwrap();
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(name: &str, x: i32, width: u32) -> MonitorInfo {
+        MonitorInfo {
+            name: Some(name.to_string()),
+            position: (x, 0),
+            size: (width, 1080),
+            scale: 1.0,
+        }
+    }
+
+    #[test]
+    fn follows_pointer_with_hysteresis() {
+        let monitors = vec![monitor("left", 0, 1920), monitor("right", 1920, 1920)];
+        let left = Some(&monitors[0]);
+
+        // no current monitor yet
+        assert_eq!(
+            monitor_at(&monitors, None, 1930.0, 10.0),
+            Some(&monitors[1])
+        );
+        // just across the edge, stay put
+        assert_eq!(
+            monitor_at(&monitors, left, 1930.0, 10.0),
+            Some(&monitors[0])
+        );
+        // well past the edge, follow
+        assert_eq!(
+            monitor_at(&monitors, left, 2000.0, 10.0),
+            Some(&monitors[1])
+        );
+        // outside every monitor
+        assert_eq!(monitor_at(&monitors, None, -500.0, 10.0), None);
+    }
+
+    #[test]
+    fn falls_back_when_monitor_is_unplugged() {
+        let monitors = vec![monitor("laptop", 0, 2560), monitor("HDMI-1", 2560, 1920)];
+        let primary = monitor("laptop", 0, 2560);
+
+        let resolved = resolve_monitor(&monitors, Some("HDMI-1"), Some(&primary));
+        assert_eq!(resolved, Some(&monitors[1]));
+
+        let unplugged = &monitors[..1];
+        let resolved = resolve_monitor(unplugged, Some("HDMI-1"), Some(&primary));
+        assert_eq!(resolved, Some(&monitors[0]));
+
+        let resolved = resolve_monitor(&monitors[1..], Some("DP-2"), None);
+        assert_eq!(resolved, Some(&monitors[1]));
+    }
+}
@@ -0,0 +1,212 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Instant, SystemTime},
+};
+
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::app::coords::{monitor_bounds, PointerSpace};
+use crate::app::event::InputEvent;
+use crate::app::monitor::{list_monitors, place_window, MonitorInfo};
+use crate::app::state::AppState;
+use crate::app::stream::EventStream;
+use crate::app::window::config_window;
+
+/// Extra mouse-only overlay covering a monitor other than the main one.
+pub struct Overlay {
+    pub label: String,
+    pub monitor: MonitorInfo,
+    pub stream: EventStream,
+}
+
+// labels are never reused, a destroyed window may not be gone yet
+static NEXT_OVERLAY: AtomicUsize = AtomicUsize::new(0);
+// one sync at a time, each works from the latest state
+static SPAN_SYNC: Mutex<()> = Mutex::new(());
+
+/// Opens an overlay on every monitor the main window isn't on, or closes
+/// them all when spanning is off. Keystrokes keep going to the main window,
+/// mouse events go wherever the pointer is.
+///
+/// Runs on its own thread: windows are built and destroyed without the app
+/// state locked, and never on the main thread, which deadlocks on Windows.
+pub fn sync_span_overlays(app_handle: &AppHandle) {
+    let app_handle = app_handle.clone();
+    thread::spawn(move || {
+        let _sync = SPAN_SYNC.lock().unwrap();
+        let Some(main_window) = app_handle.get_webview_window("main") else {
+            return;
+        };
+        // before locking, the main thread answers these and may be waiting on the lock
+        let monitors = list_monitors(&main_window);
+        let main_monitor = main_window
+            .current_monitor()
+            .ok()
+            .flatten()
+            .map(|monitor| MonitorInfo::from(&monitor));
+
+        let state = app_handle.state::<Mutex<AppState>>();
+        let (closing, opening) =
+            plan_span_overlays(&mut state.lock().unwrap(), &monitors, main_monitor.as_ref());
+
+        for overlay in closing {
+            if let Some(window) = app_handle.get_webview_window(&overlay.label) {
+                window.destroy().unwrap_or(());
+            }
+        }
+        let opened: Vec<Overlay> = opening
+            .into_iter()
+            .filter_map(|(label, monitor)| open_overlay(&app_handle, label, monitor))
+            .collect();
+        if opened.is_empty() {
+            return;
+        }
+
+        let mut app_state = state.lock().unwrap();
+        app_state.overlays.extend(opened);
+        println!("🖥️ Spanning {} extra overlay(s)", app_state.overlays.len());
+    });
+}
+
+/// Splits off the overlays no longer wanted and labels the ones to open,
+/// keeping those already on the right monitor. `main_monitor` is where the
+/// main window really is, it can lag behind a move that was just made.
+fn plan_span_overlays(
+    app_state: &mut AppState,
+    monitors: &[MonitorInfo],
+    main_monitor: Option<&MonitorInfo>,
+) -> (Vec<Overlay>, Vec<(String, MonitorInfo)>) {
+    let wanted: Vec<&MonitorInfo> = monitors
+        .iter()
+        .filter(|_| app_state.span_monitors)
+        .filter(|m| m.name != app_state.monitor_name || m.position != app_state.monitor_position)
+        .filter(|m| Some(*m) != main_monitor)
+        .collect();
+
+    // indices are about to change, moves still pending belong to the old target
+    if app_state.mouse_target.is_some() {
+        if let Some((x, y)) = app_state.mouse_coalescer.take(Instant::now()) {
+            app_state.push_event(InputEvent::MouseMoveEvent { x, y }, SystemTime::now());
+        }
+        app_state.mouse_target = None;
+    }
+    let (kept, closing): (Vec<Overlay>, Vec<Overlay>) = app_state
+        .overlays
+        .drain(..)
+        .partition(|overlay| wanted.contains(&&overlay.monitor));
+    app_state.overlays = kept;
+
+    let opening = wanted
+        .into_iter()
+        .filter(|m| !app_state.overlays.iter().any(|o| &o.monitor == *m))
+        .map(|m| {
+            let id = NEXT_OVERLAY.fetch_add(1, Ordering::Relaxed);
+            (format!("overlay-{}", id), m.clone())
+        })
+        .collect();
+    (closing, opening)
+}
+
+fn open_overlay(app_handle: &AppHandle, label: String, monitor: MonitorInfo) -> Option<Overlay> {
+    // same window setup as the main overlay, see tauri.conf.json
+    let mut config = app_handle
+        .config()
+        .app
+        .windows
+        .iter()
+        .find(|w| w.label == "main")
+        .cloned()?;
+    config.label = label;
+    config.url = WebviewUrl::App("index.html#/overlay".into());
+
+    let window = match WebviewWindowBuilder::from_config(app_handle, &config)
+        .and_then(|builder| builder.build())
+    {
+        Ok(window) => window,
+        Err(err) => {
+            eprintln!("Failed to open overlay {}: {}", config.label, err);
+            return None;
+        }
+    };
+    // after, on macOS config_window covers the primary monitor
+    config_window(&window);
+    place_window(&window, &monitor);
+
+    Some(Overlay {
+        label: config.label,
+        monitor,
+        stream: EventStream::default(),
+    })
+}
+
+/// Points mouse events at the overlay under the pointer, `None` meaning the
+/// main window.
+pub fn route_mouse(app_state: &mut AppState, x: f64, y: f64, time: SystemTime) {
+    let target = app_state
+        .overlays
+        .iter()
+        .position(|o| monitor_bounds(PointerSpace::native(), &o.monitor).contains(x, y, 0.0));
+    if target == app_state.mouse_target {
+        return;
+    }
+
+    // moves still pending belong to the overlay the pointer just left
+    if let Some((x, y)) = app_state.mouse_coalescer.take(Instant::now()) {
+        app_state.push_event(InputEvent::MouseMoveEvent { x, y }, time);
+    }
+    app_state.mouse_target = target;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(name: &str, x: i32) -> MonitorInfo {
+        MonitorInfo {
+            name: Some(name.to_string()),
+            position: (x, 0),
+            size: (1920, 1080),
+            scale: 1.0,
+        }
+    }
+
+    fn planned(
+        app_state: &mut AppState,
+        monitors: &[MonitorInfo],
+        main: Option<&MonitorInfo>,
+    ) -> Vec<String> {
+        let (_, opening) = plan_span_overlays(app_state, monitors, main);
+        opening
+            .into_iter()
+            .filter_map(|(_, monitor)| monitor.name)
+            .collect()
+    }
+
+    #[test]
+    fn skips_the_main_window_monitor() {
+        let monitors = vec![monitor("left", 0), monitor("right", 1920)];
+        let mut app_state = AppState {
+            span_monitors: true,
+            ..Default::default()
+        };
+
+        // nothing remembered yet, the window itself says where it is
+        assert_eq!(
+            planned(&mut app_state, &monitors, Some(&monitors[1])),
+            vec!["left"]
+        );
+
+        app_state.monitor_name = Some("left".to_string());
+        assert_eq!(
+            planned(&mut app_state, &monitors, Some(&monitors[1])),
+            Vec::<String>::new()
+        );
+
+        app_state.span_monitors = false;
+        assert!(planned(&mut app_state, &monitors, None).is_empty());
+    }
+}
@@ -0,0 +1,82 @@
+use std::{fs, path::PathBuf};
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::app::theme::{apply_style, export_theme, load_theme};
+
+pub const PROFILES_CHANGED: &str = "profiles-changed";
+
+/// Style profiles are exported theme files kept in `<config dir>/profiles`,
+/// named after the file, e.g. `coding.json`.
+pub fn profiles_dir(app: &AppHandle) -> Option<PathBuf> {
+    Some(app.path().app_config_dir().ok()?.join("profiles"))
+}
+
+pub fn list_profiles(app: &AppHandle) -> Vec<String> {
+    let Some(dir) = profiles_dir(app) else {
+        return vec![];
+    };
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter_map(|entry| {
+                    let path = entry.path();
+                    if path.extension()? != "json" {
+                        return None;
+                    }
+                    Some(path.file_stem()?.to_str()?.to_string())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+pub fn is_valid_profile_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == ' ')
+}
+
+/// Switches the key style to a saved profile, keeping the display setup.
+pub fn apply_profile(app: &AppHandle, name: &str) -> Result<(), String> {
+    if !is_valid_profile_name(name) {
+        return Err(format!("invalid profile name {:?}", name));
+    }
+    let path = profiles_dir(app)
+        .ok_or("no config directory")?
+        .join(format!("{}.json", name));
+    let theme = load_theme(&path).map_err(|e| format!("profile {:?}: {}", name, e))?;
+    apply_style(app, &theme.style)?;
+    println!("🎨 Switched to profile {}", name);
+    Ok(())
+}
+
+/// Saves the current key style as a profile, replacing one of the same name.
+pub fn save_profile(app: &AppHandle, name: &str) -> Result<(), String> {
+    if !is_valid_profile_name(name) {
+        return Err(format!("invalid profile name {:?}", name));
+    }
+    let dir = profiles_dir(app).ok_or("no config directory")?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    export_theme(app, &dir.join(format!("{}.json", name)))?;
+    println!("💾 Saved profile {}", name);
+    app.emit(PROFILES_CHANGED, name).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_profile_names() {
+        assert!(is_valid_profile_name("coding"));
+        assert!(is_valid_profile_name("live stream_2"));
+        assert!(!is_valid_profile_name(""));
+        assert!(!is_valid_profile_name("../store"));
+        assert!(!is_valid_profile_name("a/b"));
+    }
+}
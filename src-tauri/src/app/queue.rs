@@ -0,0 +1,52 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    mpsc::{sync_channel, Receiver, SyncSender, TrySendError},
+    Arc,
+};
+
+use rdev::Event;
+
+pub const QUEUE_CAPACITY: usize = 4096;
+
+#[derive(Debug, Default)]
+pub struct QueueCounters {
+    queued: AtomicU64,
+    overflowed: AtomicU64,
+}
+
+impl QueueCounters {
+    pub fn queued(&self) -> u64 {
+        self.queued.load(Ordering::Relaxed)
+    }
+
+    pub fn overflowed(&self) -> u64 {
+        self.overflowed.load(Ordering::Relaxed)
+    }
+}
+
+/// Producer half used inside the OS hook, never blocks.
+#[derive(Clone)]
+pub struct EventSender {
+    tx: SyncSender<Event>,
+    counters: Arc<QueueCounters>,
+}
+
+impl EventSender {
+    pub fn push(&self, event: Event) {
+        match self.tx.try_send(event) {
+            Ok(()) => {
+                self.counters.queued.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(TrySendError::Full(_)) => {
+                self.counters.overflowed.fetch_add(1, Ordering::Relaxed);
+            }
+            // worker is gone, nothing left to deliver to
+            Err(TrySendError::Disconnected(_)) => {}
+        }
+    }
+}
+
+pub fn event_queue(counters: Arc<QueueCounters>) -> (EventSender, Receiver<Event>) {
+    let (tx, rx) = sync_channel(QUEUE_CAPACITY);
+    (EventSender { tx, counters }, rx)
+}
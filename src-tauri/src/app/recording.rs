@@ -0,0 +1,154 @@
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::app::state::AppState;
+use crate::app::stream::SequencedEvent;
+
+pub const RECORDING_CHANGED: &str = "recording-changed";
+
+/// Writes the events shown on the overlay to a file, one JSON object per
+/// line, so they can be lined up with a screen capture later.
+#[derive(Default)]
+pub struct Recorder {
+    recording: Option<(PathBuf, BufWriter<File>)>,
+}
+
+impl Recorder {
+    pub fn path(&self) -> Option<&Path> {
+        self.recording.as_ref().map(|(path, _)| path.as_path())
+    }
+
+    pub fn start(&mut self, path: PathBuf) -> Result<(), String> {
+        if let Some(current) = self.path() {
+            return Err(format!("already recording to {:?}", current));
+        }
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let file = File::create(&path).map_err(|e| format!("{:?}: {}", path, e))?;
+        self.recording = Some((path, BufWriter::new(file)));
+        Ok(())
+    }
+
+    /// Closes the file, returns where it was written.
+    pub fn stop(&mut self) -> Option<PathBuf> {
+        let (path, mut writer) = self.recording.take()?;
+        if let Err(e) = writer.flush() {
+            eprintln!("Failed to finish recording {:?}: {}", path, e);
+        }
+        Some(path)
+    }
+
+    pub fn write(&mut self, batch: &[SequencedEvent]) -> Result<(), String> {
+        let Some((_, writer)) = &mut self.recording else {
+            return Ok(());
+        };
+        for event in batch {
+            serde_json::to_writer(&mut *writer, event).map_err(|e| e.to_string())?;
+            writer.write_all(b"\n").map_err(|e| e.to_string())?;
+        }
+        // a crash shouldn't take the tail of the recording with it
+        writer.flush().map_err(|e| e.to_string())
+    }
+}
+
+/// `keyviz-<unix seconds>.jsonl` in the videos folder, next to where most
+/// screen recorders put their captures.
+pub fn default_recording_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .video_dir()
+        .or_else(|_| app.path().app_data_dir())
+        .map_err(|e| e.to_string())?;
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    Ok(dir.join(format!("keyviz-{}.jsonl", secs)))
+}
+
+/// Starts recording to `path`, or a new file in the videos folder.
+pub fn start_recording(app: &AppHandle, path: Option<PathBuf>) -> Result<PathBuf, String> {
+    let path = match path {
+        Some(path) => path,
+        None => default_recording_path(app)?,
+    };
+    {
+        let state = app.state::<Mutex<AppState>>();
+        let mut app_state = state.lock().unwrap();
+        app_state.recorder.start(path.clone())?;
+    }
+    println!("⏺️ Recording to {:?}", path);
+    notify_recording(app, Some(&path));
+    Ok(path)
+}
+
+/// Stops the recording, returns the file it went to if there was one.
+pub fn stop_recording(app: &AppHandle) -> Option<PathBuf> {
+    let path = {
+        let state = app.state::<Mutex<AppState>>();
+        let mut app_state = state.lock().unwrap();
+        app_state.recorder.stop()
+    }?;
+    println!("⏹️ Recording saved to {:?}", path);
+    notify_recording(app, None);
+    Some(path)
+}
+
+pub fn notify_recording(app: &AppHandle, path: Option<&Path>) {
+    if let Err(e) = app.emit(RECORDING_CHANGED, path) {
+        eprintln!("Failed to emit {}: {}", RECORDING_CHANGED, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::event::InputEvent;
+    use crate::app::stream::EventStream;
+
+    #[test]
+    fn writes_one_event_per_line() {
+        let path = std::env::temp_dir().join(format!(
+            "keyviz-recording-{}/keys.jsonl",
+            std::process::id()
+        ));
+        let mut stream = EventStream::default();
+        stream.push(
+            InputEvent::MouseMoveEvent { x: 1.0, y: 2.0 },
+            SystemTime::now(),
+        );
+        stream.push(
+            InputEvent::MouseMoveEvent { x: 3.0, y: 4.0 },
+            SystemTime::now(),
+        );
+        let batch = stream.take_batch().unwrap();
+
+        let mut recorder = Recorder::default();
+        recorder.write(&batch).unwrap();
+        recorder.start(path.clone()).unwrap();
+        assert!(recorder.start(path.clone()).is_err());
+        recorder.write(&batch).unwrap();
+        assert_eq!(recorder.stop(), Some(path.clone()));
+        assert_eq!(recorder.stop(), None);
+
+        let content = fs::read_to_string(&path).unwrap();
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+        let seqs: Vec<u64> = content
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<serde_json::Value>(line).unwrap()["seq"]
+                    .as_u64()
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(seqs, vec![1, 2]);
+    }
+}
@@ -0,0 +1,183 @@
+use std::time::{Duration, SystemTime};
+
+use serde::Deserialize;
+
+use crate::app::annotations::normalize_chord;
+use crate::app::event::InputEvent;
+use crate::app::headless::ComboFormatter;
+
+pub const DEFAULT_SEQUENCE_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// A sequence as configured in the settings, e.g. `{"keys": "Ctrl+X Ctrl+S",
+/// "name": "Save Buffer"}`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SequenceDefinition {
+    pub keys: String,
+    pub name: String,
+}
+
+struct Sequence {
+    steps: Vec<String>,
+    name: String,
+}
+
+/// Spots leader key and chord sequences like `g g` or `Space f f` in the
+/// stream of combos.
+pub struct SequenceDetector {
+    /// longest first, so `g g` doesn't shadow `Space g g`
+    sequences: Vec<Sequence>,
+    /// longest gap allowed between two steps
+    pub timeout: Duration,
+    combos: ComboFormatter,
+    /// recent combos, as pressed and normalized
+    recent: Vec<(String, String)>,
+    last_at: Option<SystemTime>,
+}
+
+impl Default for SequenceDetector {
+    fn default() -> Self {
+        Self {
+            sequences: vec![],
+            timeout: DEFAULT_SEQUENCE_TIMEOUT,
+            combos: ComboFormatter::default(),
+            recent: vec![],
+            last_at: None,
+        }
+    }
+}
+
+impl SequenceDetector {
+    pub fn set_sequences(&mut self, definitions: &[SequenceDefinition]) {
+        self.sequences = definitions
+            .iter()
+            .filter_map(|definition| {
+                let steps: Option<Vec<String>> = definition
+                    .keys
+                    .split_whitespace()
+                    .map(normalize_chord)
+                    .collect();
+                match steps {
+                    Some(steps) if steps.len() >= 2 => Some(Sequence {
+                        steps,
+                        name: definition.name.clone(),
+                    }),
+                    _ => {
+                        eprintln!("Ignoring sequence {:?}", definition.keys);
+                        None
+                    }
+                }
+            })
+            .collect();
+        self.sequences
+            .sort_by_key(|sequence| std::cmp::Reverse(sequence.steps.len()));
+        self.recent.clear();
+    }
+
+    /// Returns a `SequenceEvent` when `event` completes a known sequence.
+    pub fn feed(&mut self, event: &InputEvent, time: SystemTime) -> Option<InputEvent> {
+        // keep the formatter in step even with nothing to detect
+        let combo = self.combos.format(event)?;
+        let longest = self.sequences.first()?.steps.len();
+        let normalized = normalize_chord(&combo)?;
+
+        let expired = self.last_at.is_some_and(|last| {
+            time.duration_since(last)
+                .is_ok_and(|gap| gap > self.timeout)
+        });
+        if expired {
+            self.recent.clear();
+        }
+        self.last_at = Some(time);
+        self.recent.push((combo, normalized));
+        if self.recent.len() > longest {
+            self.recent.drain(..self.recent.len() - longest);
+        }
+
+        let recent = &self.recent;
+        let (len, name) = self
+            .sequences
+            .iter()
+            .find(|sequence| {
+                recent.len() >= sequence.steps.len()
+                    && recent[recent.len() - sequence.steps.len()..]
+                        .iter()
+                        .map(|(_, normalized)| normalized)
+                        .eq(sequence.steps.iter())
+            })
+            .map(|sequence| (sequence.steps.len(), sequence.name.clone()))?;
+
+        let start = self.recent.len() - len;
+        let steps = self
+            .recent
+            .drain(..)
+            .skip(start)
+            .map(|(combo, _)| combo.split('+').map(String::from).collect())
+            .collect();
+        Some(InputEvent::SequenceEvent { steps, name })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tap(detector: &mut SequenceDetector, keys: &[&str], millis: u64) -> Option<String> {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_millis(millis);
+        let mut found = None;
+        let events = keys
+            .iter()
+            .map(|key| (key, true))
+            .chain(keys.iter().rev().map(|key| (key, false)));
+        for (key, pressed) in events {
+            let event = InputEvent::KeyEvent {
+                pressed,
+                name: key.to_string(),
+                held_ms: None,
+                repeat: false,
+            };
+            if let Some(InputEvent::SequenceEvent { name, .. }) = detector.feed(&event, time) {
+                found = Some(name);
+            }
+        }
+        found
+    }
+
+    #[test]
+    fn detects_sequences() {
+        let mut detector = SequenceDetector::default();
+        detector.set_sequences(&[
+            SequenceDefinition {
+                keys: "g g".to_string(),
+                name: "Top".to_string(),
+            },
+            SequenceDefinition {
+                keys: "Ctrl+X Ctrl+S".to_string(),
+                name: "Save Buffer".to_string(),
+            },
+            SequenceDefinition {
+                keys: "Space f f".to_string(),
+                name: "Find File".to_string(),
+            },
+        ]);
+
+        assert_eq!(tap(&mut detector, &["KeyG"], 0), None);
+        assert_eq!(tap(&mut detector, &["KeyG"], 300), Some("Top".to_string()));
+
+        assert_eq!(tap(&mut detector, &["ControlLeft", "KeyX"], 1000), None);
+        assert_eq!(
+            tap(&mut detector, &["ControlLeft", "KeyS"], 1200),
+            Some("Save Buffer".to_string())
+        );
+
+        assert_eq!(tap(&mut detector, &["Space"], 2000), None);
+        assert_eq!(tap(&mut detector, &["KeyF"], 2100), None);
+        assert_eq!(
+            tap(&mut detector, &["KeyF"], 2200),
+            Some("Find File".to_string())
+        );
+
+        // too slow
+        assert_eq!(tap(&mut detector, &["KeyG"], 5000), None);
+        assert_eq!(tap(&mut detector, &["KeyG"], 6500), None);
+    }
+}
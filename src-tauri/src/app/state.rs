@@ -1,31 +1,134 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime},
+};
+
 use serde::Deserialize;
-use tauri::{image::Image, include_image, Emitter, Wry};
+use serde_json::Value;
+use tauri::{image::Image, include_image, menu::MenuItem, Emitter, Manager, Wry};
 use tauri_plugin_store::StoreExt;
 
+use crate::app::activation::ActivationRules;
+use crate::app::annotations::{load_annotations, Annotator};
+use crate::app::coalesce::{MouseMoveCoalescer, DEFAULT_MOUSE_MOVE_RATE};
+use crate::app::event::{held_ms, InputEvent, MouseButton};
+use crate::app::idle::IdleTracker;
+use crate::app::listener::ListenerStatus;
+use crate::app::monitor::MonitorInfo;
+use crate::app::overlay::Overlay;
+use crate::app::recording::Recorder;
+use crate::app::sequence::{SequenceDefinition, SequenceDetector};
+use crate::app::stats::{load_statistics, StatsRecorder};
+use crate::app::store::{read_store_state, KEY_EVENT_STORE, KEY_STYLE_STORE, STORE_FILE};
+use crate::app::stream::EventStream;
+use crate::app::tray::TRAY_ID;
+use crate::app::typing::{TypingTracker, DEFAULT_PRIVATE_APPS};
+
 #[derive(Default)]
 pub struct AppState {
     pub listening: bool,
+    pub listener_status: ListenerStatus,
+    pub idle: IdleTracker,
+    pub activation: ActivationRules,
     pub pressed_keys: Vec<String>,
+    pub key_press_times: HashMap<String, SystemTime>,
+    /// release keys held longer than this, off by default so long holds show
+    pub stuck_key_timeout: Option<Duration>,
     pub toggle_shortcut: Vec<String>,
+    pub arrange_shortcut: Vec<String>,
+    /// overlay takes the cursor to be dragged around
+    pub interactive: bool,
+    /// send OS auto-repeats of held keys instead of dropping them
+    pub key_repeats: bool,
 
+    /// monitor picked in the settings, `monitor_name` is where the overlay actually is
+    pub preferred_monitor: Option<String>,
     pub monitor_name: Option<String>,
     pub monitor_scale: f64,
     pub monitor_position: (i32, i32),
+    pub monitors: Vec<MonitorInfo>,
+    pub follow_cursor: bool,
+    pub span_monitors: bool,
+    pub overlays: Vec<Overlay>,
+    /// index into `overlays` receiving mouse events, `None` for the main window
+    pub mouse_target: Option<usize>,
+
+    pub mouse_coalescer: MouseMoveCoalescer,
+    pub mouse_position: (f64, f64),
+    pub pressed_buttons: Vec<MouseButton>,
+    pub stream: EventStream,
+
+    pub stats: StatsRecorder,
+    pub annotator: Annotator,
+    pub sequences: SequenceDetector,
+    pub typing: TypingTracker,
+    pub recorder: Recorder,
+    /// app owning the focused window, only tracked while something needs it
+    pub foreground_app: Option<String>,
+
+    /// webview settings the tray shows, kept current by `apply_store_update`
+    pub show_mouse: bool,
+    pub show_history: bool,
 }
 
 impl AppState {
     pub fn new(app: &tauri::AppHandle) -> Self {
         let mut toggle_shortcut = vec!["Shift".to_string(), "F10".to_string()];
+        let mut arrange_shortcut = vec!["ShiftLeft".to_string(), "F9".to_string()];
+        let mut collect_statistics = false;
+        let mut key_repeats = false;
+        let mut mouse_move_rate = DEFAULT_MOUSE_MOVE_RATE;
+        let mut stuck_key_timeout = None;
+        let mut idle = IdleTracker::default();
+        let mut activation = ActivationRules::default();
+        let mut show_annotations = false;
+        let mut sequences = SequenceDetector::default();
+        let mut typing = TypingTracker::default();
+        let mut show_history = false;
+        let mut private_apps: Vec<String> = DEFAULT_PRIVATE_APPS
+            .iter()
+            .map(|app| app.to_string())
+            .collect();
 
         // load saved config from store
-        if let Ok(store) = app.store("store.json") {
-            if let Some(value) = store.get("key_event_store") {
+        if let Ok(store) = app.store(STORE_FILE) {
+            if let Some(value) = store.get(KEY_EVENT_STORE) {
                 // the value comes in as a String: "{\"state\": ...}"
                 if let Some(json_str) = value.as_str() {
                     // parse the inner string
                     match serde_json::from_str::<KeyEventStore>(json_str) {
                         Ok(parsed) => {
                             toggle_shortcut = parsed.state.toggle_shortcut;
+                            if let Some(shortcut) = parsed.state.arrange_shortcut {
+                                arrange_shortcut = shortcut;
+                            }
+                            collect_statistics = parsed.state.collect_statistics;
+                            key_repeats = parsed.state.show_key_timing;
+                            if let Some(rate) = parsed.state.mouse_move_rate {
+                                mouse_move_rate = rate;
+                            }
+                            stuck_key_timeout = parsed
+                                .state
+                                .stuck_key_timeout_secs
+                                .filter(|secs| *secs > 0)
+                                .map(Duration::from_secs);
+                            idle.timeout = parsed
+                                .state
+                                .idle_timeout_secs
+                                .filter(|secs| *secs > 0)
+                                .map(Duration::from_secs);
+                            idle.hide_windows = parsed.state.hide_when_idle;
+                            activation = parsed.state.activation_rules;
+                            show_annotations = parsed.state.show_annotations;
+                            sequences.set_sequences(&parsed.state.sequences);
+                            if let Some(timeout) = parsed.state.sequence_timeout_ms {
+                                sequences.timeout = Duration::from_millis(timeout);
+                            }
+                            typing.enabled = parsed.state.typing_mode;
+                            show_history = parsed.state.show_event_history;
+                            if let Some(apps) = parsed.state.private_apps {
+                                private_apps = apps;
+                            }
                         }
                         Err(e) => eprintln!("Failed to parse inner config JSON: {}", e),
                     }
@@ -33,39 +136,186 @@ impl AppState {
             }
         }
 
+        typing.set_private_apps(&private_apps);
+        let style = read_store_state(app, KEY_STYLE_STORE).unwrap_or_default();
+
         Self {
             listening: true,
+            listener_status: ListenerStatus::Starting,
+            idle,
+            activation,
             pressed_keys: vec![],
+            key_press_times: HashMap::new(),
+            stuck_key_timeout,
             toggle_shortcut,
+            arrange_shortcut,
+            interactive: false,
+            key_repeats,
+            preferred_monitor: None,
             monitor_name: None,
             monitor_scale: 1.0,
             monitor_position: (0, 0),
+            monitors: vec![],
+            follow_cursor: false,
+            span_monitors: false,
+            overlays: vec![],
+            mouse_target: None,
+            mouse_coalescer: MouseMoveCoalescer::new(mouse_move_rate),
+            mouse_position: (0.0, 0.0),
+            pressed_buttons: vec![],
+            stream: EventStream::default(),
+            stats: StatsRecorder {
+                enabled: collect_statistics,
+                stats: load_statistics(app),
+                ..Default::default()
+            },
+            annotator: Annotator {
+                enabled: show_annotations,
+                db: load_annotations(app),
+                ..Default::default()
+            },
+            sequences,
+            typing,
+            recorder: Recorder::default(),
+            foreground_app: None,
+            show_mouse: mouse_shown(&style["mouse"]),
+            show_history,
+        }
+    }
+
+    /// Follows a `{key, value}` store event for the settings mirrored here.
+    pub fn apply_store_update(&mut self, store_key: &str, update: &Value) {
+        let value = &update["value"];
+        match (store_key, update["key"].as_str()) {
+            (KEY_STYLE_STORE, Some("mouse")) => self.show_mouse = mouse_shown(value),
+            (KEY_EVENT_STORE, Some("showEventHistory")) => {
+                self.show_history = value.as_bool().unwrap_or(false)
+            }
+            (KEY_EVENT_STORE, Some("activationRules")) => {
+                if let Ok(rules) = serde_json::from_value(value.clone()) {
+                    self.activation = rules;
+                }
+            }
+            _ => {}
         }
     }
-    pub fn toggle_listener(&mut self, app: &tauri::AppHandle, toggle: &tauri::menu::MenuItem<Wry>) {
+    pub fn toggle_listener(&mut self, app: &tauri::AppHandle, toggle: &MenuItem<Wry>) {
         self.listening = !self.listening;
 
         if self.listening {
             println!("🟢 Listening enabled");
-            toggle.set_text("Stop").unwrap();
-            app.tray_by_id("keyviz-tray")
-                .unwrap()
-                .set_icon(Some(Image::from(include_image!("icons/tray.png"))))
-                .unwrap();
         } else {
             println!("🔴 Listening disabled");
-            toggle.set_text("Start").unwrap();
-            app.tray_by_id("keyviz-tray")
-                .unwrap()
-                .set_icon(Some(Image::from(include_image!("icons/tray-disabled.png"))))
-                .unwrap();
         }
+        self.show_tray(app, Some(toggle));
+
+        // every overlay, not just main, when spanning monitors
+        if let Err(e) = app.emit("listening-toggle", self.listening) {
+            eprintln!("Failed to emit listening toggle: {}", e);
+        }
+    }
+
+    /// Brings the tray icon, tooltip and Start/Stop item in line with the
+    /// listener. Only posted to the main thread: menus and trays wait on it,
+    /// and it takes this state's lock for commands and menu events.
+    pub fn show_tray(&self, app: &tauri::AppHandle, toggle: Option<&MenuItem<Wry>>) {
+        let listening = self.listening;
+        let failure = match &self.listener_status {
+            ListenerStatus::Failed { diagnostic, .. } => Some(diagnostic.message),
+            _ => None,
+        };
+        let toggle = toggle.cloned().or_else(|| {
+            app.try_state::<MenuItem<Wry>>()
+                .map(|item| item.inner().clone())
+        });
+        let handle = app.clone();
+        let posted = app.run_on_main_thread(move || {
+            if let Some(toggle) = toggle {
+                let text = if listening { "Stop" } else { "Start" };
+                if let Err(e) = toggle.set_text(text) {
+                    eprintln!("Failed to update the tray menu: {}", e);
+                }
+            }
+            let Some(tray) = handle.tray_by_id(TRAY_ID) else {
+                return;
+            };
+            // a failed listener keeps the disabled icon until it recovers
+            let icon = if listening && failure.is_none() {
+                include_image!("icons/tray.png")
+            } else {
+                include_image!("icons/tray-disabled.png")
+            };
+            let tooltip = failure.map_or("Keyviz".to_string(), |message| {
+                format!("Keyviz: {}", message)
+            });
+            if let Err(e) = tray
+                .set_icon(Some(Image::from(icon)))
+                .and_then(|_| tray.set_tooltip(Some(tooltip)))
+            {
+                eprintln!("Failed to update the tray: {}", e);
+            }
+        });
+        if let Err(e) = posted {
+            eprintln!("Failed to update the tray: {}", e);
+        }
+    }
+
+    /// Queues an event for the window it belongs to: keystrokes always go to
+    /// the main window, mouse events to the overlay under the pointer.
+    pub fn push_event(&mut self, event: InputEvent, time: SystemTime) {
+        match event {
+            InputEvent::KeyEvent { .. }
+            | InputEvent::ComboEvent { .. }
+            | InputEvent::SequenceEvent { .. }
+            | InputEvent::TextEvent { .. } => self.stream.push(event, time),
+            _ => self.mouse_stream().push(event, time),
+        }
+    }
+
+    pub fn mouse_stream(&mut self) -> &mut EventStream {
+        match self.mouse_target.and_then(|i| self.overlays.get_mut(i)) {
+            Some(overlay) => &mut overlay.stream,
+            None => &mut self.stream,
+        }
+    }
+
+    /// Origin and scale of the monitor mouse events are relative to.
+    pub fn mouse_origin(&self) -> ((i32, i32), f64) {
+        match self.mouse_target.and_then(|i| self.overlays.get(i)) {
+            Some(overlay) => (overlay.monitor.position, overlay.monitor.scale),
+            None => (self.monitor_position, self.monitor_scale),
+        }
+    }
+
+    /// Forgets a pressed key and tells the webview it was released.
+    pub fn release_key(&mut self, key_name: &str, time: SystemTime) {
+        if !self.pressed_keys.iter().any(|k| k == key_name) {
+            return;
+        }
+        self.pressed_keys.retain(|k| k != key_name);
+        let pressed_at = self.key_press_times.remove(key_name);
 
-        app.emit_to("main", "listening-toggle", self.listening)
-            .unwrap();
+        if self.listening {
+            self.stream.push(
+                InputEvent::KeyEvent {
+                    pressed: false,
+                    name: key_name.to_string(),
+                    held_ms: held_ms(pressed_at.as_ref(), time),
+                    repeat: false,
+                },
+                time,
+            );
+        }
     }
 }
 
+/// Whether clicks or the cursor indicator show, with the webview's defaults
+/// until something is saved.
+fn mouse_shown(mouse: &Value) -> bool {
+    mouse["showClicks"].as_bool().unwrap_or(false)
+        || mouse["showIndicator"].as_bool().unwrap_or(true)
+}
+
 #[derive(Debug, Deserialize)]
 struct KeyEventStore {
     pub state: KeyEventState,
@@ -78,9 +328,30 @@ struct KeyEventState {
     // pub drag_threshold: u32,
     // pub filter_hotkeys: bool,
     // pub ignore_modifiers: Vec<String>,
-    // pub show_event_history: bool,
     // pub max_history: u32,
     // pub linger_duration_ms: u32,
     // pub show_mouse_events: bool,
     pub toggle_shortcut: Vec<String>,
+    pub arrange_shortcut: Option<Vec<String>>,
+    #[serde(default)]
+    pub show_key_timing: bool,
+    pub mouse_move_rate: Option<u32>,
+    pub stuck_key_timeout_secs: Option<u64>,
+    pub idle_timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub hide_when_idle: bool,
+    #[serde(default)]
+    pub activation_rules: ActivationRules,
+    #[serde(default)]
+    pub collect_statistics: bool,
+    #[serde(default)]
+    pub show_annotations: bool,
+    #[serde(default)]
+    pub sequences: Vec<SequenceDefinition>,
+    pub sequence_timeout_ms: Option<u64>,
+    #[serde(default)]
+    pub typing_mode: bool,
+    #[serde(default)]
+    pub show_event_history: bool,
+    pub private_apps: Option<Vec<String>>,
 }
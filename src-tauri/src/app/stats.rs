@@ -0,0 +1,264 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::PathBuf,
+    sync::Mutex,
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::app::event::InputEvent;
+use crate::app::headless::ComboFormatter;
+use crate::app::state::AppState;
+
+const STATS_FILE: &str = "statistics.json";
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Counts for one day, only aggregates: nothing here can be read back as text.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DayStats {
+    pub keys: BTreeMap<String, u64>,
+    /// presses with modifiers held, e.g. "ControlLeft+KeyC"
+    pub combos: BTreeMap<String, u64>,
+    pub clicks: BTreeMap<String, u64>,
+    /// key presses per foreground app
+    pub apps: BTreeMap<String, u64>,
+    /// keys and combos per foreground app, missing from older files
+    #[serde(default)]
+    pub app_usage: BTreeMap<String, AppStats>,
+}
+
+/// Counts for the keys pressed while one app was in the foreground.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AppStats {
+    pub keys: BTreeMap<String, u64>,
+    pub combos: BTreeMap<String, u64>,
+}
+
+/// Usage per day, keyed by UTC date, e.g. "2024-05-01".
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Statistics {
+    pub days: BTreeMap<String, DayStats>,
+}
+
+impl Statistics {
+    /// Days between `from` and `to`, both inclusive and optional.
+    pub fn range(&self, from: Option<&str>, to: Option<&str>) -> Statistics {
+        let days = self
+            .days
+            .iter()
+            .filter(|(day, _)| from.is_none_or(|from| day.as_str() >= from))
+            .filter(|(day, _)| to.is_none_or(|to| day.as_str() <= to))
+            .map(|(day, stats)| (day.clone(), stats.clone()))
+            .collect();
+        Statistics { days }
+    }
+}
+
+/// Opt-in collection of key and click counts from the event stream.
+#[derive(Default)]
+pub struct StatsRecorder {
+    pub enabled: bool,
+    pub stats: Statistics,
+    /// changed since the last save
+    pub dirty: bool,
+    combos: ComboFormatter,
+}
+
+impl StatsRecorder {
+    /// Counts `event`, `app` being the foreground app it went to.
+    pub fn record(&mut self, event: &InputEvent, time: SystemTime, app: Option<&str>) {
+        if !self.enabled {
+            return;
+        }
+        let combo = self
+            .combos
+            .format(event)
+            .filter(|combo| combo.contains('+'));
+        let counted = matches!(
+            event,
+            InputEvent::KeyEvent { pressed: true, .. }
+                | InputEvent::MouseButtonEvent { pressed: true, .. }
+        );
+        if !counted && combo.is_none() {
+            return;
+        }
+        let day = self.stats.days.entry(day_of(time)).or_default();
+
+        match event {
+            InputEvent::KeyEvent {
+                pressed: true,
+                name,
+                ..
+            } => {
+                *day.keys.entry(name.clone()).or_default() += 1;
+                if let Some(app) = app {
+                    *day.apps.entry(app.to_string()).or_default() += 1;
+                    let usage = day.app_usage.entry(app.to_string()).or_default();
+                    *usage.keys.entry(name.clone()).or_default() += 1;
+                }
+            }
+            InputEvent::MouseButtonEvent {
+                pressed: true,
+                button,
+            } => *day.clicks.entry(format!("{:?}", button)).or_default() += 1,
+            _ => {}
+        }
+        if let Some(combo) = combo {
+            if let Some(app) = app {
+                let usage = day.app_usage.entry(app.to_string()).or_default();
+                *usage.combos.entry(combo.clone()).or_default() += 1;
+            }
+            *day.combos.entry(combo).or_default() += 1;
+        }
+        self.dirty = true;
+    }
+}
+
+/// UTC date of `time` as "YYYY-MM-DD".
+pub fn day_of(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// days since 1970-01-01 to a proleptic Gregorian date, after Howard Hinnant
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+fn stats_path(app: &AppHandle) -> Option<PathBuf> {
+    Some(app.path().app_data_dir().ok()?.join(STATS_FILE))
+}
+
+pub fn load_statistics(app: &AppHandle) -> Statistics {
+    stats_path(app)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_statistics(app: &AppHandle, stats: &Statistics) -> Result<(), String> {
+    let path = stats_path(app).ok_or("no data directory")?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string(stats).map_err(|e| e.to_string())?;
+    fs::write(path, content).map_err(|e| e.to_string())
+}
+
+/// Writes counts collected since the last save.
+pub fn flush_statistics(app: &AppHandle) {
+    let state = app.state::<Mutex<AppState>>();
+    let stats = {
+        let mut app_state = state.lock().unwrap();
+        if !app_state.stats.dirty {
+            return;
+        }
+        app_state.stats.dirty = false;
+        app_state.stats.stats.clone()
+    };
+    if let Err(e) = save_statistics(app, &stats) {
+        eprintln!("Failed to save statistics: {}", e);
+    }
+}
+
+/// Saves the counts now and then.
+pub fn start_stats_worker(app_handle: AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(SAVE_INTERVAL);
+        flush_statistics(&app_handle);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::event::MouseButton;
+
+    fn key(name: &str, pressed: bool) -> InputEvent {
+        InputEvent::KeyEvent {
+            pressed,
+            name: name.to_string(),
+            held_ms: None,
+            repeat: false,
+        }
+    }
+
+    #[test]
+    fn formats_days() {
+        assert_eq!(day_of(UNIX_EPOCH), "1970-01-01");
+        // 2024-02-29T23:59:59Z
+        assert_eq!(
+            day_of(UNIX_EPOCH + Duration::from_secs(1_709_251_199)),
+            "2024-02-29"
+        );
+        assert_eq!(
+            day_of(UNIX_EPOCH + Duration::from_secs(1_709_251_200)),
+            "2024-03-01"
+        );
+    }
+
+    #[test]
+    fn counts_keys_combos_and_clicks() {
+        let mut recorder = StatsRecorder {
+            enabled: true,
+            ..Default::default()
+        };
+        let time = UNIX_EPOCH;
+        for event in [
+            key("ControlLeft", true),
+            key("KeyC", true),
+            key("KeyC", false),
+            key("ControlLeft", false),
+            key("KeyC", true),
+            key("KeyC", false),
+            InputEvent::MouseButtonEvent {
+                pressed: true,
+                button: MouseButton::Left,
+            },
+        ] {
+            recorder.record(&event, time, Some("Code"));
+        }
+
+        let day = &recorder.stats.days["1970-01-01"];
+        assert_eq!(day.keys["KeyC"], 2);
+        assert_eq!(day.keys["ControlLeft"], 1);
+        assert_eq!(day.combos.len(), 1);
+        assert_eq!(day.combos["ControlLeft+KeyC"], 1);
+        assert_eq!(day.clicks["Left"], 1);
+        assert_eq!(day.apps["Code"], 3);
+        assert_eq!(day.app_usage["Code"].keys["KeyC"], 2);
+        assert_eq!(day.app_usage["Code"].combos["ControlLeft+KeyC"], 1);
+        assert!(recorder.dirty);
+    }
+
+    #[test]
+    fn reads_files_without_app_usage() {
+        let stats: Statistics = serde_json::from_str(
+            r#"{"days":{"2024-05-01":{"keys":{},"combos":{},"clicks":{},"apps":{"Code":1}}}}"#,
+        )
+        .unwrap();
+        assert!(stats.days["2024-05-01"].app_usage.is_empty());
+    }
+}
@@ -0,0 +1,77 @@
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_store::StoreExt;
+
+pub const STORE_FILE: &str = "store.json";
+pub const KEY_EVENT_STORE: &str = "key_event_store";
+pub const KEY_STYLE_STORE: &str = "key_style_store";
+
+/// The `state` of a zustand store persisted by the webview, if it was ever saved.
+pub fn read_store_state(app: &AppHandle, store_key: &str) -> Option<Value> {
+    let store = app.store(STORE_FILE).ok()?;
+    // the value comes in as a String: "{\"state\": ...}"
+    let persisted = store.get(store_key)?;
+    let mut persisted: Value = serde_json::from_str(persisted.as_str()?).ok()?;
+    Some(persisted["state"].take())
+}
+
+/// Replaces one top-level key of a persisted zustand store and broadcasts it
+/// the same way the settings window syncs its stores.
+pub fn set_store_value(
+    app: &AppHandle,
+    store_key: &str,
+    key: &str,
+    value: Value,
+) -> Result<(), String> {
+    set_store_values(app, store_key, vec![(key.to_string(), value)])
+}
+
+/// Like `set_store_value` for several keys, saved together so a failure
+/// leaves none of them changed.
+pub fn set_store_values(
+    app: &AppHandle,
+    store_key: &str,
+    values: Vec<(String, Value)>,
+) -> Result<(), String> {
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    let mut persisted = store
+        .get(store_key)
+        .and_then(|v| {
+            v.as_str()
+                .and_then(|s| serde_json::from_str::<Value>(s).ok())
+        })
+        .unwrap_or_else(|| json!({ "state": {}, "version": 0 }));
+
+    for (key, value) in &values {
+        persisted["state"][key] = value.clone();
+    }
+    store.set(store_key, persisted.to_string());
+    store.save().map_err(|e| e.to_string())?;
+
+    for (key, value) in values {
+        app.emit(store_key, json!({ "key": key, "value": value }))
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Merges `patch` into an object already saved under `key`. Fails when the
+/// webview never saved it, a partial object would shadow its defaults.
+pub fn patch_store_value(
+    app: &AppHandle,
+    store_key: &str,
+    key: &str,
+    patch: Value,
+) -> Result<(), String> {
+    let mut value = read_store_state(app, store_key)
+        .and_then(|mut state| state.get_mut(key).map(Value::take))
+        .filter(Value::is_object)
+        .ok_or_else(|| format!("no saved {} settings yet", key))?;
+
+    if let (Some(value), Some(patch)) = (value.as_object_mut(), patch.as_object()) {
+        for (field, field_value) in patch {
+            value.insert(field.clone(), field_value.clone());
+        }
+    }
+    set_store_value(app, store_key, key, value)
+}
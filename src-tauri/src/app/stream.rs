@@ -0,0 +1,111 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::app::event::InputEvent;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SequencedEvent {
+    pub seq: u64,
+    /// milliseconds since the unix epoch, never decreasing
+    pub time: f64,
+    #[serde(flatten)]
+    pub event: InputEvent,
+}
+
+/// Collects outgoing events into batches, numbering them so the webview can
+/// tell when some were lost.
+#[derive(Default)]
+pub struct EventStream {
+    next_seq: u64,
+    last_time: f64,
+    pending: Vec<SequencedEvent>,
+}
+
+impl EventStream {
+    pub fn push(&mut self, event: InputEvent, time: SystemTime) {
+        let time = time
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs_f64() * 1000.0)
+            .unwrap_or_default()
+            .max(self.last_time);
+        self.last_time = time;
+        self.next_seq += 1;
+        self.pending.push(SequencedEvent {
+            seq: self.next_seq,
+            time,
+            event,
+        });
+    }
+
+    /// Burns sequence numbers for events that never made it here,
+    /// leaving a gap the webview can detect.
+    pub fn skip(&mut self, count: u64) {
+        self.next_seq += count;
+    }
+
+    /// Sequence number of the last event handed out.
+    pub fn seq(&self) -> u64 {
+        self.next_seq
+    }
+
+    pub fn take_batch(&mut self) -> Option<Vec<SequencedEvent>> {
+        if self.pending.is_empty() {
+            return None;
+        }
+        Some(std::mem::take(&mut self.pending))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn key(name: &str) -> InputEvent {
+        InputEvent::KeyEvent {
+            pressed: true,
+            name: name.to_string(),
+            held_ms: None,
+            repeat: false,
+        }
+    }
+
+    #[test]
+    fn numbers_events_and_keeps_time_monotonic() {
+        let now = SystemTime::now();
+        let mut stream = EventStream::default();
+
+        stream.push(key("KeyA"), now);
+        stream.push(key("KeyB"), now - Duration::from_millis(5));
+        stream.skip(2);
+        stream.push(key("KeyC"), now + Duration::from_millis(5));
+
+        let batch = stream.take_batch().unwrap();
+        let seqs: Vec<u64> = batch.iter().map(|e| e.seq).collect();
+        assert_eq!(seqs, vec![1, 2, 5]);
+        assert_eq!(batch[0].time, batch[1].time);
+        assert!(batch[2].time > batch[1].time);
+        assert_eq!(stream.seq(), 5);
+        assert!(stream.take_batch().is_none());
+    }
+
+    #[test]
+    fn serializes_flat() {
+        let mut stream = EventStream::default();
+        stream.push(key("KeyA"), UNIX_EPOCH + Duration::from_millis(7));
+
+        let json = serde_json::to_value(stream.take_batch().unwrap()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([{
+                "seq": 1,
+                "time": 7.0,
+                "type": "KeyEvent",
+                "pressed": true,
+                "name": "KeyA",
+            }])
+        );
+    }
+}
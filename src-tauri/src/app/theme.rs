@@ -0,0 +1,1011 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::Read,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use quick_xml::events::{attributes::Attribute, BytesStart, Event};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::app::store::{read_store_state, set_store_values, KEY_STYLE_STORE};
+
+/// Version of the theme format written by this build, older ones are
+/// migrated on import.
+pub const THEME_VERSION: u64 = 2;
+/// A theme bundled with its fonts and icons: a tar archive holding the theme
+/// as `theme.json` next to the files it refers to.
+pub const ARCHIVE_EXTENSION: &str = "keyviz-theme";
+const THEME_ENTRY: &str = "theme.json";
+
+const MAX_THEME_SIZE: usize = 256 * 1024;
+const MAX_ARCHIVE_SIZE: u64 = 16 * 1024 * 1024;
+const MAX_ASSET_SIZE: usize = 4 * 1024 * 1024;
+const MAX_ARCHIVE_ENTRIES: usize = 256;
+const MAX_ICONS: usize = 128;
+// what icons may draw with, anything else goes along with its content:
+// scripts, foreignObject, animations rewriting attributes, external images
+const SVG_ELEMENTS: &[&str] = &[
+    "svg",
+    "g",
+    "defs",
+    "title",
+    "desc",
+    "symbol",
+    "use",
+    "path",
+    "rect",
+    "circle",
+    "ellipse",
+    "line",
+    "polyline",
+    "polygon",
+    "text",
+    "tspan",
+    "textPath",
+    "linearGradient",
+    "radialGradient",
+    "stop",
+    "clipPath",
+    "mask",
+    "pattern",
+    "marker",
+    "filter",
+    "feBlend",
+    "feColorMatrix",
+    "feComponentTransfer",
+    "feComposite",
+    "feDropShadow",
+    "feFlood",
+    "feFuncA",
+    "feFuncB",
+    "feFuncG",
+    "feFuncR",
+    "feGaussianBlur",
+    "feMerge",
+    "feMergeNode",
+    "feMorphology",
+    "feOffset",
+];
+
+// ───────────── Schema, mirrors KeyStyleState ─────────────
+
+/// A hex color as the color picker writes it, e.g. "#ffffff99".
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Color(String);
+
+impl Color {
+    fn hex(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl TryFrom<String> for Color {
+    type Error = String;
+
+    // anything else would end up in a style attribute as is
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let valid = value.strip_prefix('#').is_some_and(|hex| {
+            matches!(hex.len(), 3 | 4 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit())
+        });
+        if valid {
+            Ok(Self(value))
+        } else {
+            let shown: String = value.chars().take(16).collect();
+            Err(format!("invalid color {:?}", shown))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Alignment {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    CenterLeft,
+    Center,
+    CenterRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FlexDirection {
+    Row,
+    Column,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Animation {
+    None,
+    Fade,
+    Zoom,
+    Float,
+    Slide,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeycapStyle {
+    Minimal,
+    Laptop,
+    Lowprofile,
+    Pbt,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IconAlignment {
+    FlexStart,
+    Center,
+    FlexEnd,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Caps {
+    Uppercase,
+    Capitalize,
+    Lowercase,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TextVariant {
+    Icon,
+    Text,
+    TextShort,
+}
+
+/// `appearance` without the display setup, which stays with the machine.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Appearance {
+    pub flex_direction: FlexDirection,
+    pub alignment: Alignment,
+    pub margin_x: f64,
+    pub margin_y: f64,
+    pub animation: Animation,
+    pub animation_duration: f64,
+    pub style: KeycapStyle,
+}
+
+impl Default for Appearance {
+    fn default() -> Self {
+        Self {
+            flex_direction: FlexDirection::Column,
+            alignment: Alignment::BottomCenter,
+            margin_x: 100.0,
+            margin_y: 100.0,
+            animation: Animation::Fade,
+            animation_duration: 0.25,
+            style: KeycapStyle::Lowprofile,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Layout {
+    pub show_icon: bool,
+    pub show_symbol: bool,
+    pub show_press_count: bool,
+    pub icon_alignment: IconAlignment,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self {
+            show_icon: true,
+            show_symbol: true,
+            show_press_count: true,
+            icon_alignment: IconAlignment::FlexEnd,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Colors {
+    pub color: Color,
+    pub secondary_color: Color,
+    pub use_gradient: bool,
+}
+
+impl Default for Colors {
+    fn default() -> Self {
+        Self {
+            color: Color::hex("#ffffff"),
+            secondary_color: Color::hex("#1a1a1a"),
+            use_gradient: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Modifier {
+    pub highlight: bool,
+    pub color: Color,
+    pub secondary_color: Color,
+    pub text_color: Color,
+    pub border_color: Color,
+}
+
+impl Default for Modifier {
+    fn default() -> Self {
+        Self {
+            highlight: false,
+            color: Color::hex("#3a86ff"),
+            secondary_color: Color::hex("#000000"),
+            text_color: Color::hex("#000000"),
+            border_color: Color::hex("#000000"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Text {
+    pub size: f64,
+    pub color: Color,
+    pub caps: Caps,
+    pub variant: TextVariant,
+    pub alignment: Alignment,
+}
+
+impl Default for Text {
+    fn default() -> Self {
+        Self {
+            size: 32.0,
+            color: Color::hex("#000000"),
+            caps: Caps::Capitalize,
+            variant: TextVariant::TextShort,
+            alignment: Alignment::Center,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Border {
+    pub enabled: bool,
+    pub color: Color,
+    pub width: f64,
+    pub radius: f64,
+}
+
+impl Default for Border {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            color: Color::hex("#1a1a1a"),
+            width: 2.0,
+            radius: 0.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Background {
+    pub enabled: bool,
+    pub color: Color,
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            color: Color::hex("#ffffff99"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Mouse {
+    pub show_clicks: bool,
+    pub size: f64,
+    pub color: Color,
+    pub keep_highlight: bool,
+    pub show_indicator: bool,
+    pub keep_indicator: bool,
+    pub indicator_size: f64,
+    pub indicator_offset_x: f64,
+    pub indicator_offset_y: f64,
+}
+
+impl Default for Mouse {
+    fn default() -> Self {
+        Self {
+            show_clicks: false,
+            size: 150.0,
+            color: Color::hex("#009dff"),
+            keep_highlight: false,
+            show_indicator: true,
+            keep_indicator: true,
+            indicator_size: 50.0,
+            indicator_offset_x: 50.0,
+            indicator_offset_y: 50.0,
+        }
+    }
+}
+
+/// The key style a theme carries. Missing fields take the webview's
+/// defaults, unknown ones are dropped.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyStyle {
+    pub appearance: Appearance,
+    pub layout: Layout,
+    pub color: Colors,
+    pub modifier: Modifier,
+    pub text: Text,
+    pub border: Border,
+    pub background: Background,
+    pub mouse: Mouse,
+}
+
+fn check_range(field: &str, value: f64, range: RangeInclusive<f64>) -> Result<(), String> {
+    if range.contains(&value) {
+        Ok(())
+    } else {
+        Err(format!("{} is out of range", field))
+    }
+}
+
+impl KeyStyle {
+    fn validate(&self) -> Result<(), String> {
+        let Self {
+            appearance,
+            text,
+            border,
+            mouse,
+            ..
+        } = self;
+        check_range("appearance.marginX", appearance.margin_x, 0.0..=10_000.0)?;
+        check_range("appearance.marginY", appearance.margin_y, 0.0..=10_000.0)?;
+        check_range(
+            "appearance.animationDuration",
+            appearance.animation_duration,
+            0.0..=10.0,
+        )?;
+        check_range("text.size", text.size, 1.0..=500.0)?;
+        check_range("border.width", border.width, 0.0..=100.0)?;
+        check_range("border.radius", border.radius, 0.0..=1.0)?;
+        check_range("mouse.size", mouse.size, 0.0..=1_000.0)?;
+        check_range("mouse.indicatorSize", mouse.indicator_size, 0.0..=1_000.0)?;
+        check_range(
+            "mouse.indicatorOffsetX",
+            mouse.indicator_offset_x,
+            -1_000.0..=1_000.0,
+        )?;
+        check_range(
+            "mouse.indicatorOffsetY",
+            mouse.indicator_offset_y,
+            -1_000.0..=1_000.0,
+        )
+    }
+}
+
+/// Files of an archive a theme refers to, by their path in the archive.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AssetPaths {
+    /// font the key labels are set in, e.g. "fonts/Inter.woff2"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font: Option<String>,
+    /// icons replacing the built-in ones, by key name, e.g. "Return"
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub icons: BTreeMap<String, String>,
+}
+
+impl AssetPaths {
+    fn is_empty(&self) -> bool {
+        self.font.is_none() && self.icons.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ThemeFile {
+    version: u64,
+    #[serde(flatten)]
+    style: KeyStyle,
+    #[serde(default, skip_serializing_if = "AssetPaths::is_empty")]
+    assets: AssetPaths,
+}
+
+/// A font or icon, checked to be what it claims.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Asset {
+    pub mime: &'static str,
+    pub data: Vec<u8>,
+}
+
+impl Asset {
+    fn extension(&self) -> &'static str {
+        match self.mime {
+            "font/ttf" => "ttf",
+            "font/otf" => "otf",
+            "font/woff" => "woff",
+            "font/woff2" => "woff2",
+            "image/png" => "png",
+            _ => "svg",
+        }
+    }
+
+    fn data_url(&self) -> String {
+        format!("data:{};base64,{}", self.mime, BASE64.encode(&self.data))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Theme {
+    pub style: KeyStyle,
+    pub font: Option<Asset>,
+    pub icons: BTreeMap<String, Asset>,
+}
+
+// ───────────── Parsing ─────────────
+
+/// Upgrades a theme written by an older keyviz in place.
+fn migrate(theme: &mut Value, version: u64) {
+    if version < 2 {
+        // unversioned exports of the settings window, which carried the
+        // monitor setup along with the looks
+        if let Some(appearance) = theme["appearance"].as_object_mut() {
+            appearance.remove("monitor");
+            appearance.remove("spanMonitors");
+        }
+    }
+    theme["version"] = json!(THEME_VERSION);
+}
+
+/// Checks a theme against the schema, `files` being the rest of its archive.
+pub fn parse_theme(content: &[u8], files: &BTreeMap<String, Vec<u8>>) -> Result<Theme, String> {
+    if content.len() > MAX_THEME_SIZE {
+        return Err("theme is too large".to_string());
+    }
+    let mut value: Value =
+        serde_json::from_slice(content).map_err(|e| format!("invalid theme: {}", e))?;
+    if !value.is_object() {
+        return Err("invalid theme: not an object".to_string());
+    }
+    let version = match value.get("version") {
+        None => 1,
+        Some(version) => version.as_u64().ok_or("invalid theme version")?,
+    };
+    if version > THEME_VERSION {
+        return Err(format!("theme version {} needs a newer keyviz", version));
+    }
+    migrate(&mut value, version);
+
+    let file: ThemeFile =
+        serde_json::from_value(value).map_err(|e| format!("invalid theme: {}", e))?;
+    file.style.validate()?;
+
+    let font = file
+        .assets
+        .font
+        .as_deref()
+        .map(|path| load_asset(files, path, "font/"))
+        .transpose()?;
+    if file.assets.icons.len() > MAX_ICONS {
+        return Err("too many icons in theme".to_string());
+    }
+    let mut icons = BTreeMap::new();
+    for (key, path) in &file.assets.icons {
+        if !is_valid_key_name(key) {
+            return Err(format!("invalid key name {:?}", key));
+        }
+        icons.insert(key.clone(), load_asset(files, path, "image/")?);
+    }
+    Ok(Theme {
+        style: file.style,
+        font,
+        icons,
+    })
+}
+
+/// rdev key names, e.g. "KeyA" or "ShiftLeft".
+fn is_valid_key_name(name: &str) -> bool {
+    !name.is_empty() && name.len() <= 32 && name.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+fn load_asset(files: &BTreeMap<String, Vec<u8>>, path: &str, kind: &str) -> Result<Asset, String> {
+    let data = files
+        .get(path)
+        .ok_or_else(|| format!("{} is missing from the theme", path))?;
+    if data.len() > MAX_ASSET_SIZE {
+        return Err(format!("{} is too large", path));
+    }
+    let data = match sniff(data) {
+        Some("image/svg+xml") if kind == "image/" => sanitize_svg(data),
+        Some(mime) if mime.starts_with(kind) => Some(data.clone()),
+        _ => None,
+    };
+    match data {
+        Some(data) => Ok(Asset {
+            mime: sniff(&data).unwrap_or_default(),
+            data,
+        }),
+        None => Err(format!(
+            "{} is not a supported {}",
+            path,
+            kind.trim_end_matches('/')
+        )),
+    }
+}
+
+/// Media type of a supported font or icon, judged by its content.
+fn sniff(data: &[u8]) -> Option<&'static str> {
+    let mime = match data.get(..4)? {
+        [0, 1, 0, 0] | b"true" => "font/ttf",
+        b"OTTO" => "font/otf",
+        b"wOFF" => "font/woff",
+        b"wOF2" => "font/woff2",
+        _ if data.starts_with(b"\x89PNG\r\n\x1a\n") => "image/png",
+        _ => {
+            let svg = std::str::from_utf8(data).ok()?;
+            let svg = svg.trim_start_matches('\u{feff}').trim_start();
+            if !(svg.starts_with("<svg") || svg.starts_with("<?xml")) {
+                return None;
+            }
+            "image/svg+xml"
+        }
+    };
+    Some(mime)
+}
+
+/// The SVG rewritten with only the elements in `SVG_ELEMENTS` and without
+/// event handlers, script URLs or references to other files. Documents with
+/// a DTD or processing instructions are refused outright.
+fn sanitize_svg(data: &[u8]) -> Option<Vec<u8>> {
+    let svg = std::str::from_utf8(data).ok()?;
+    let mut reader = quick_xml::Reader::from_str(svg.trim_start_matches('\u{feff}'));
+    let mut writer = quick_xml::Writer::new(Vec::new());
+    let mut root = false;
+    // depth inside an element being dropped
+    let mut dropping = 0;
+    loop {
+        let event = reader.read_event().ok()?;
+        if let Event::Start(element) | Event::Empty(element) = &event {
+            if !root && element.name().as_ref() != b"svg" {
+                return None;
+            }
+            root = true;
+        }
+        match event {
+            Event::Start(_) if dropping > 0 => dropping += 1,
+            Event::End(_) if dropping > 0 => dropping -= 1,
+            Event::Start(element) => match clean_svg_element(&element) {
+                Some(element) => writer.write_event(Event::Start(element)).ok()?,
+                None => dropping = 1,
+            },
+            Event::Empty(element) => {
+                if let Some(element) = clean_svg_element(&element).filter(|_| dropping == 0) {
+                    writer.write_event(Event::Empty(element)).ok()?;
+                }
+            }
+            Event::End(element) => writer.write_event(Event::End(element)).ok()?,
+            event @ (Event::Text(_) | Event::CData(_) | Event::GeneralRef(_)) => {
+                if dropping == 0 {
+                    writer.write_event(event).ok()?;
+                }
+            }
+            Event::Decl(_) | Event::Comment(_) => {}
+            // entities and stylesheets
+            Event::DocType(_) | Event::PI(_) => return None,
+            Event::Eof => break,
+        }
+    }
+    root.then(|| writer.into_inner())
+}
+
+/// A copy of an allowed element with the attributes that are safe to keep.
+fn clean_svg_element(element: &BytesStart) -> Option<BytesStart<'static>> {
+    let name = std::str::from_utf8(element.name().as_ref())
+        .ok()?
+        .to_string();
+    if !SVG_ELEMENTS.contains(&name.as_str()) {
+        return None;
+    }
+    let mut clean = BytesStart::new(name);
+    for attribute in element.attributes() {
+        let attribute = attribute.ok()?;
+        if is_safe_svg_attribute(&attribute) {
+            clean.push_attribute(attribute);
+        }
+    }
+    Some(clean)
+}
+
+fn is_safe_svg_attribute(attribute: &Attribute) -> bool {
+    let key = String::from_utf8_lossy(attribute.key.as_ref()).to_lowercase();
+    let Ok(value) = attribute.unescape_value() else {
+        return false;
+    };
+    // browsers skip whitespace inside URLs, "java\tscript:" still runs
+    let value: String = value
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .collect::<String>()
+        .to_lowercase();
+    let (prefix, local) = match key.split_once(':') {
+        Some((prefix, local)) => (Some(prefix), local),
+        None => (None, key.as_str()),
+    };
+    if key == "xmlns" || prefix == Some("xmlns") {
+        return true;
+    }
+    if !matches!(prefix, None | Some("xlink") | Some("xml")) || local.starts_with("on") {
+        return false;
+    }
+    // only fragments of this same document
+    if local == "href" {
+        return value.starts_with('#');
+    }
+    !value.contains("javascript:")
+        && value
+            .split("url(")
+            .skip(1)
+            .all(|url| url.trim_start_matches(['"', '\'']).starts_with('#'))
+}
+
+/// Reads a `.json` theme or a `.keyviz-theme` archive.
+pub fn load_theme(path: &Path) -> Result<Theme, String> {
+    let size = fs::metadata(path).map_err(|e| e.to_string())?.len();
+    let archive = path.extension().is_some_and(|ext| ext == ARCHIVE_EXTENSION);
+    let limit = if archive {
+        MAX_ARCHIVE_SIZE
+    } else {
+        MAX_THEME_SIZE as u64
+    };
+    if size > limit {
+        return Err("theme is too large".to_string());
+    }
+    let mut content = Vec::new();
+    fs::File::open(path)
+        .and_then(|file| file.take(limit).read_to_end(&mut content))
+        .map_err(|e| e.to_string())?;
+
+    if archive {
+        let mut files = read_tar(&content)?;
+        let theme = files
+            .remove(THEME_ENTRY)
+            .ok_or("theme archive has no theme.json")?;
+        parse_theme(&theme, &files)
+    } else {
+        parse_theme(&content, &BTreeMap::new())
+    }
+}
+
+// ───────────── Archives ─────────────
+
+/// Regular files of a tar archive by path. Links, directories and the like
+/// are skipped, entries are only ever looked up by the paths a theme names.
+fn read_tar(data: &[u8]) -> Result<BTreeMap<String, Vec<u8>>, String> {
+    let corrupt = |_| "corrupt theme archive".to_string();
+    let mut archive = tar::Archive::new(data);
+    let mut files = BTreeMap::new();
+    for entry in archive.entries().map_err(corrupt)? {
+        let mut entry = entry.map_err(corrupt)?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        if files.len() == MAX_ARCHIVE_ENTRIES {
+            return Err("too many files in theme archive".to_string());
+        }
+        let name = String::from_utf8_lossy(&entry.path_bytes())
+            .trim_start_matches("./")
+            .to_string();
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content).map_err(corrupt)?;
+        files.insert(name, content);
+    }
+    Ok(files)
+}
+
+/// A ustar archive of `files`.
+fn write_tar(files: &[(String, &[u8])]) -> Result<Vec<u8>, String> {
+    let mtime = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let mut builder = tar::Builder::new(Vec::new());
+    for (name, data) in files {
+        let mut header = tar::Header::new_ustar();
+        header.set_entry_type(tar::EntryType::Regular);
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(mtime);
+        builder
+            .append_data(&mut header, name, *data)
+            .map_err(|e| e.to_string())?;
+    }
+    builder.into_inner().map_err(|e| e.to_string())
+}
+
+// ───────────── Applying ─────────────
+
+/// Fonts and icons of the imported theme live in `<config dir>/theme`.
+fn assets_dir(app: &AppHandle) -> Option<PathBuf> {
+    Some(app.path().app_config_dir().ok()?.join("theme"))
+}
+
+/// Assets of the imported theme, checked again as they are read.
+pub fn installed_assets(app: &AppHandle) -> (Option<Asset>, BTreeMap<String, Asset>) {
+    let read = |path: PathBuf| -> Option<Asset> {
+        let data = fs::read(path)
+            .ok()
+            .filter(|data| data.len() <= MAX_ASSET_SIZE)?;
+        Some(Asset {
+            mime: sniff(&data)?,
+            data,
+        })
+    };
+    let Some(dir) = assets_dir(app) else {
+        return (None, BTreeMap::new());
+    };
+    let files = |dir: PathBuf| -> Vec<PathBuf> {
+        fs::read_dir(dir)
+            .map(|entries| entries.filter_map(Result::ok).map(|e| e.path()).collect())
+            .unwrap_or_default()
+    };
+
+    let font = files(dir.clone())
+        .into_iter()
+        .filter(|path| path.file_stem().is_some_and(|stem| stem == "font"))
+        .find_map(read)
+        .filter(|asset| asset.mime.starts_with("font/"));
+    let icons = files(dir.join("icons"))
+        .into_iter()
+        .filter_map(|path| {
+            let key = path.file_stem()?.to_str()?.to_string();
+            let asset = read(path).filter(|asset| asset.mime.starts_with("image/"))?;
+            is_valid_key_name(&key).then_some((key, asset))
+        })
+        .take(MAX_ICONS)
+        .collect();
+    (font, icons)
+}
+
+fn remove_dir(dir: &Path) -> Result<(), String> {
+    match fs::remove_dir_all(dir) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+        _ => Ok(()),
+    }
+}
+
+/// Writes the theme's fonts and icons next to the installed ones, for
+/// `commit_assets` to swap in.
+fn stage_assets(app: &AppHandle, theme: &Theme) -> Result<PathBuf, String> {
+    let staged = assets_dir(app)
+        .ok_or("no config directory")?
+        .with_file_name("theme.new");
+    // left over from an import that failed
+    remove_dir(&staged)?;
+    let write = || -> Result<(), String> {
+        fs::create_dir_all(staged.join("icons")).map_err(|e| e.to_string())?;
+        // named by us, never after paths in the archive
+        if let Some(font) = &theme.font {
+            fs::write(
+                staged.join(format!("font.{}", font.extension())),
+                &font.data,
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        for (key, icon) in &theme.icons {
+            fs::write(
+                staged
+                    .join("icons")
+                    .join(format!("{}.{}", key, icon.extension())),
+                &icon.data,
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    };
+    if let Err(e) = write() {
+        let _ = remove_dir(&staged);
+        return Err(e);
+    }
+    Ok(staged)
+}
+
+/// Replaces the installed assets with the ones staged.
+fn commit_assets(app: &AppHandle, staged: &Path) -> Result<(), String> {
+    let dir = assets_dir(app).ok_or("no config directory")?;
+    remove_dir(&dir)?;
+    fs::rename(staged, &dir).map_err(|e| e.to_string())
+}
+
+/// Switches the key style over, keeping the display setup.
+pub fn apply_style(app: &AppHandle, style: &KeyStyle) -> Result<(), String> {
+    let mut style = serde_json::to_value(style).map_err(|e| e.to_string())?;
+    if let Some(current) = read_store_state(app, KEY_STYLE_STORE) {
+        for field in ["monitor", "spanMonitors"] {
+            if let Some(value) = current["appearance"].get(field) {
+                style["appearance"][field] = value.clone();
+            }
+        }
+    }
+    let Value::Object(sections) = style else {
+        return Ok(());
+    };
+    set_store_values(app, KEY_STYLE_STORE, sections.into_iter().collect())
+}
+
+/// Imports a theme file or archive. The assets are written aside first, a
+/// theme that can't be installed leaves the current one as it was.
+pub fn import_theme(app: &AppHandle, path: &Path) -> Result<(), String> {
+    let theme = load_theme(path)?;
+    let staged = stage_assets(app, &theme)?;
+    if let Err(e) = apply_style(app, &theme.style) {
+        let _ = remove_dir(&staged);
+        return Err(e);
+    }
+    commit_assets(app, &staged)?;
+    if let Err(e) = app.emit("theme-assets-changed", ()) {
+        eprintln!("Failed to emit theme-assets-changed: {}", e);
+    }
+    println!("🎨 Imported theme {:?}", path);
+    Ok(())
+}
+
+/// Saves the current key style, with the imported fonts and icons when
+/// `path` is a `.keyviz-theme` archive.
+pub fn export_theme(app: &AppHandle, path: &Path) -> Result<(), String> {
+    let style = match read_store_state(app, KEY_STYLE_STORE) {
+        Some(state) => serde_json::from_value(state).map_err(|e| e.to_string())?,
+        None => KeyStyle::default(),
+    };
+    let mut file = ThemeFile {
+        version: THEME_VERSION,
+        style,
+        assets: AssetPaths::default(),
+    };
+    if !path.extension().is_some_and(|ext| ext == ARCHIVE_EXTENSION) {
+        let content = serde_json::to_string_pretty(&file).map_err(|e| e.to_string())?;
+        return fs::write(path, content).map_err(|e| e.to_string());
+    }
+
+    let (font, icons) = installed_assets(app);
+    let mut entries = vec![];
+    if let Some(font) = &font {
+        let name = format!("fonts/font.{}", font.extension());
+        file.assets.font = Some(name.clone());
+        entries.push((name, font.data.as_slice()));
+    }
+    for (key, icon) in &icons {
+        let name = format!("icons/{}.{}", key, icon.extension());
+        file.assets.icons.insert(key.clone(), name.clone());
+        entries.push((name, icon.data.as_slice()));
+    }
+    let content = serde_json::to_vec_pretty(&file).map_err(|e| e.to_string())?;
+    entries.insert(0, (THEME_ENTRY.to_string(), content.as_slice()));
+    fs::write(path, write_tar(&entries)?).map_err(|e| e.to_string())
+}
+
+/// The imported fonts and icons as data URLs for the webview.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ThemeAssets {
+    pub font: Option<String>,
+    pub icons: BTreeMap<String, String>,
+}
+
+pub fn theme_assets(app: &AppHandle) -> ThemeAssets {
+    let (font, icons) = installed_assets(app);
+    ThemeAssets {
+        font: font.map(|font| font.data_url()),
+        icons: icons
+            .into_iter()
+            .map(|(key, icon)| (key, icon.data_url()))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrates_and_validates_themes() {
+        // an unversioned export with fields from an older release missing
+        let legacy = br##"{
+            "appearance": { "monitor": "DP-1", "alignment": "top-left", "marginX": 20 },
+            "text": { "color": "#ff0000" },
+            "unknown": true
+        }"##;
+        let theme = parse_theme(legacy, &BTreeMap::new()).unwrap();
+        assert_eq!(theme.style.appearance.alignment, Alignment::TopLeft);
+        assert_eq!(theme.style.appearance.margin_y, 100.0);
+        assert_eq!(theme.style.text.color, Color::hex("#ff0000"));
+        assert_eq!(theme.style.mouse, Mouse::default());
+
+        let invalid: [&[u8]; 5] = [
+            br#"{ "version": 3 }"#,
+            br#"{ "color": { "color": "red; background: url(https://x)" } }"#,
+            br#"{ "text": { "size": 1e9 } }"#,
+            br#"{ "appearance": { "style": "retro" } }"#,
+            br#"[]"#,
+        ];
+        for theme in invalid {
+            assert!(parse_theme(theme, &BTreeMap::new()).is_err());
+        }
+        assert!(parse_theme(&vec![b' '; MAX_THEME_SIZE + 1], &BTreeMap::new()).is_err());
+    }
+
+    #[test]
+    fn reads_bundled_assets() {
+        let theme = br#"{
+            "version": 2,
+            "assets": { "font": "fonts/a.woff2", "icons": { "Return": "icons/return.svg" } }
+        }"#;
+        let font = b"wOF2 font data".as_slice();
+        let svg = br#"<svg xmlns="http://www.w3.org/2000/svg"></svg>"#.as_slice();
+        let archive = write_tar(&[
+            (THEME_ENTRY.to_string(), theme.as_slice()),
+            ("fonts/a.woff2".to_string(), font),
+            ("./icons/return.svg".to_string(), svg),
+        ])
+        .unwrap();
+
+        let mut files = read_tar(&archive).unwrap();
+        assert_eq!(files.len(), 3);
+        let theme = parse_theme(&files.remove(THEME_ENTRY).unwrap(), &files).unwrap();
+        let font = theme.font.unwrap();
+        assert_eq!((font.mime, font.extension()), ("font/woff2", "woff2"));
+        let icon = &theme.icons["Return"];
+        assert_eq!((icon.mime, icon.extension()), ("image/svg+xml", "svg"));
+
+        // an icon that is really something else
+        files.insert("icons/return.svg".to_string(), b"<html><script>".to_vec());
+        let theme = br#"{ "assets": { "icons": { "Return": "icons/return.svg" } } }"#;
+        assert!(parse_theme(theme, &files).is_err());
+        let theme = br#"{ "assets": { "icons": { "../x": "icons/return.svg" } } }"#;
+        assert!(parse_theme(theme, &files).is_err());
+
+        let mut corrupt = archive.clone();
+        corrupt[0] ^= 1;
+        assert!(read_tar(&corrupt).is_err());
+        assert_eq!(
+            icon.data_url(),
+            format!("data:image/svg+xml;base64,{}", BASE64.encode(svg))
+        );
+    }
+
+    #[test]
+    fn sanitizes_svg_icons() {
+        let clean =
+            |svg: &str| sanitize_svg(svg.as_bytes()).map(|svg| String::from_utf8(svg).unwrap());
+
+        let icon = r##"<?xml version="1.0"?><svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" viewBox="0 0 8 8"><defs><path id="a" d="M0 0h8"/></defs><use xlink:href="#a" fill="url(#g)"/></svg>"##;
+        assert_eq!(
+            clean(icon).unwrap(),
+            icon.trim_start_matches(r#"<?xml version="1.0"?>"#)
+        );
+
+        let dirty = r#"<svg onload="alert(1)" width="8"><script>alert(1)</script><foreignObject><div>x</div></foreignObject><a href="javascript:alert(1)"><rect/></a><rect style="fill: url( 'https://x/y')"/><use href="https://x/y#a"/><image href="data:image/png;base64,AA"/><circle fill="java&#9;script:x" r="1"/></svg>"#;
+        assert_eq!(
+            clean(dirty).unwrap(),
+            r#"<svg width="8"><rect/><use/><circle r="1"/></svg>"#
+        );
+
+        assert!(clean(r#"<!DOCTYPE svg [<!ENTITY x "y">]><svg>&x;</svg>"#).is_none());
+        assert!(clean(r#"<?xml-stylesheet href="x.css"?><svg/>"#).is_none());
+        assert!(clean(r#"<html><svg/></html>"#).is_none());
+        assert!(clean(r#"<svg><g></svg>"#).is_none());
+    }
+}
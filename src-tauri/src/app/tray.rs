@@ -0,0 +1,277 @@
+use std::sync::Mutex;
+
+use serde_json::{json, Value};
+use tauri::{
+    menu::{CheckMenuItem, IsMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu},
+    AppHandle, Listener, Manager, Wry,
+};
+
+use crate::app::control::{execute, ControlRequest};
+use crate::app::monitor::list_monitors;
+use crate::app::profile::{list_profiles, PROFILES_CHANGED};
+use crate::app::recording::{start_recording, stop_recording, RECORDING_CHANGED};
+use crate::app::state::AppState;
+use crate::app::stats::flush_statistics;
+use crate::app::store::{
+    patch_store_value, read_store_state, set_store_value, KEY_EVENT_STORE, KEY_STYLE_STORE,
+};
+use crate::app::window::open_settings_window;
+
+pub const TRAY_ID: &str = "keyviz-tray";
+// same as the settings window's monitor option
+const FOLLOW_CURSOR: &str = "follow-cursor";
+
+/// What the menu shows, rebuilt only when this changes.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct TrayState {
+    monitors: Vec<String>,
+    monitor: Option<String>,
+    profiles: Vec<String>,
+    mouse: bool,
+    history: bool,
+    recording: bool,
+}
+
+impl TrayState {
+    /// Reads the settings, with `update` being a `{key, value}` store event
+    /// that may not have reached the store file yet.
+    fn read(app: &AppHandle, update: Option<(&str, Value)>) -> Self {
+        let mut style = read_store_state(app, KEY_STYLE_STORE).unwrap_or_default();
+        if let Some((KEY_STYLE_STORE, update)) = &update {
+            if let Some(key) = update["key"].as_str() {
+                style[key] = update["value"].clone();
+            }
+        }
+        let monitors = app
+            .get_webview_window("main")
+            .map(|window| list_monitors(&window))
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|monitor| monitor.name)
+            .collect();
+        let profiles = list_profiles(app);
+
+        // after listing monitors, the main thread answers that. Nothing
+        // holding the lock waits on the main thread, the listener, idle and
+        // control paths post their tray updates
+        let state = app.state::<Mutex<AppState>>();
+        let mut app_state = state.lock().unwrap();
+        if let Some((store_key, update)) = &update {
+            app_state.apply_store_update(store_key, update);
+        }
+        Self {
+            monitors,
+            monitor: style["appearance"]["monitor"].as_str().map(String::from),
+            profiles,
+            mouse: app_state.show_mouse,
+            history: app_state.show_history,
+            recording: app_state.recorder.path().is_some(),
+        }
+    }
+}
+
+/// The tray menu, kept in agreement with the settings.
+pub struct TrayMenu {
+    toggle_item: MenuItem<Wry>,
+    shown: Mutex<Option<TrayState>>,
+}
+
+impl TrayMenu {
+    pub fn new(toggle_item: MenuItem<Wry>) -> Self {
+        Self {
+            toggle_item,
+            shown: Mutex::new(None),
+        }
+    }
+
+    /// Rebuilds the menu if the monitors, profiles or settings changed.
+    fn sync(&self, app: &AppHandle, update: Option<(&str, Value)>) {
+        let state = TrayState::read(app, update);
+        let mut shown = self.shown.lock().unwrap();
+        if shown.as_ref() == Some(&state) {
+            return;
+        }
+        let Some(tray) = app.tray_by_id(TRAY_ID) else {
+            return;
+        };
+        match self.build(app, &state) {
+            Ok(menu) => {
+                tray.set_menu(Some(menu)).unwrap();
+                *shown = Some(state);
+            }
+            Err(e) => eprintln!("Failed to build tray menu: {}", e),
+        }
+    }
+
+    fn build(&self, app: &AppHandle, state: &TrayState) -> tauri::Result<Menu<Wry>> {
+        let monitors = Submenu::with_id(app, "monitors", "Monitor", true)?;
+        monitors.append(&CheckMenuItem::with_id(
+            app,
+            format!("monitor:{}", FOLLOW_CURSOR),
+            "Follow Cursor",
+            true,
+            state.monitor.as_deref() == Some(FOLLOW_CURSOR),
+            None::<&str>,
+        )?)?;
+        monitors.append(&PredefinedMenuItem::separator(app)?)?;
+        for name in &state.monitors {
+            monitors.append(&CheckMenuItem::with_id(
+                app,
+                format!("monitor:{}", name),
+                name,
+                true,
+                state.monitor.as_ref() == Some(name),
+                None::<&str>,
+            )?)?;
+        }
+
+        let profiles = Submenu::with_id(app, "profiles", "Profile", true)?;
+        if state.profiles.is_empty() {
+            profiles.append(&MenuItem::new(
+                app,
+                "No Saved Profiles",
+                false,
+                None::<&str>,
+            )?)?;
+        }
+        for name in &state.profiles {
+            profiles.append(&MenuItem::with_id(
+                app,
+                format!("profile:{}", name),
+                name,
+                true,
+                None::<&str>,
+            )?)?;
+        }
+
+        let mouse =
+            CheckMenuItem::with_id(app, "mouse", "Show Mouse", true, state.mouse, None::<&str>)?;
+        let history =
+            CheckMenuItem::with_id(app, "history", "History", true, state.history, None::<&str>)?;
+        let record = CheckMenuItem::with_id(
+            app,
+            "record",
+            "Record Keystrokes",
+            true,
+            state.recording,
+            None::<&str>,
+        )?;
+        let settings = MenuItem::with_id(app, "settings", "Settings", true, None::<&str>)?;
+        let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+        // an item is only shown once, each separator is its own
+        let top_separator = PredefinedMenuItem::separator(app)?;
+        let bottom_separator = PredefinedMenuItem::separator(app)?;
+
+        let items: [&dyn IsMenuItem<Wry>; 10] = [
+            &self.toggle_item,
+            &top_separator,
+            &monitors,
+            &profiles,
+            &mouse,
+            &history,
+            &record,
+            &bottom_separator,
+            &settings,
+            &quit,
+        ];
+        Menu::with_items(app, &items)
+    }
+
+    /// Rebuilds the menu even if nothing changed, check items tick
+    /// themselves when clicked.
+    fn refresh(&self, app: &AppHandle) {
+        *self.shown.lock().unwrap() = None;
+        self.sync(app, None);
+    }
+}
+
+/// Keeps the menu current as settings, monitors and profiles change.
+pub fn start_tray_sync(app_handle: &AppHandle) {
+    for name in [
+        KEY_EVENT_STORE,
+        KEY_STYLE_STORE,
+        "monitors-changed",
+        PROFILES_CHANGED,
+        RECORDING_CHANGED,
+    ] {
+        let handle = app_handle.clone();
+        app_handle.listen_any(name, move |event| {
+            // the settings window emits before saving
+            let update = serde_json::from_str::<Value>(event.payload())
+                .ok()
+                .filter(|_| name == KEY_EVENT_STORE || name == KEY_STYLE_STORE);
+            let app = handle.clone();
+            // menus live on the main thread, don't wait on it from here
+            let _ = handle.run_on_main_thread(move || {
+                app.state::<TrayMenu>()
+                    .sync(&app, update.map(|update| (name, update)));
+            });
+        });
+    }
+    app_handle.state::<TrayMenu>().sync(app_handle, None);
+}
+
+pub fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
+    let toggle_item = app.state::<MenuItem<Wry>>();
+    let id = event.id.as_ref();
+    let result = match id {
+        "toggle" => {
+            let state = app.state::<Mutex<AppState>>();
+            let mut app_state = state.lock().unwrap();
+            app_state.toggle_listener(app, &toggle_item);
+            Ok(())
+        }
+        "settings" => {
+            open_settings_window(app);
+            Ok(())
+        }
+        "quit" => {
+            flush_statistics(app);
+            std::process::exit(0)
+        }
+        // the store events bring the app state along
+        "mouse" => {
+            let shown = app.state::<Mutex<AppState>>().lock().unwrap().show_mouse;
+            patch_store_value(
+                app,
+                KEY_STYLE_STORE,
+                "mouse",
+                json!({ "showClicks": !shown, "showIndicator": !shown }),
+            )
+        }
+        "history" => {
+            let shown = app.state::<Mutex<AppState>>().lock().unwrap().show_history;
+            set_store_value(
+                app,
+                KEY_EVENT_STORE,
+                "showEventHistory",
+                Value::Bool(!shown),
+            )
+        }
+        // to a new file in the videos folder, see `keyviz ctl record`
+        "record" => match stop_recording(app) {
+            Some(_) => Ok(()),
+            None => start_recording(app, None).map(|_| ()),
+        },
+        // the overlay picks follow mode up from the setting
+        "monitor:follow-cursor" => patch_store_value(
+            app,
+            KEY_STYLE_STORE,
+            "appearance",
+            json!({ "monitor": FOLLOW_CURSOR }),
+        ),
+        _ => {
+            if let Some(name) = id.strip_prefix("monitor:") {
+                execute(app, &toggle_item, ControlRequest::Monitor(name.to_string())).map(|_| ())
+            } else if let Some(name) = id.strip_prefix("profile:") {
+                execute(app, &toggle_item, ControlRequest::Profile(name.to_string())).map(|_| ())
+            } else {
+                Err(format!("unknown tray menu item {:?}", id))
+            }
+        }
+    };
+    if let Err(e) = result {
+        eprintln!("{}", e);
+    }
+    app.state::<TrayMenu>().refresh(app);
+}
@@ -0,0 +1,270 @@
+use rdev::{Key, UnicodeInfo};
+
+use crate::app::event::InputEvent;
+
+/// Apps typed text never shows in, matched against the foreground app.
+pub const DEFAULT_PRIVATE_APPS: &[&str] = &[
+    "1Password",
+    "Bitwarden",
+    "Dashlane",
+    "KeePass",
+    "KeePassXC",
+    "Keychain Access",
+];
+
+// AltGr and Shift type characters, these make shortcuts
+const SHORTCUT_MODIFIERS: [&str; 5] = [
+    "ControlLeft",
+    "ControlRight",
+    "Alt",
+    "MetaLeft",
+    "MetaRight",
+];
+
+/// Turns key presses into the text they type, for the typing ticker.
+#[derive(Default)]
+pub struct TypingTracker {
+    pub enabled: bool,
+    /// lowercase, see `DEFAULT_PRIVATE_APPS`
+    private_apps: Vec<String>,
+    /// dead key waiting for the letter it accents
+    pending_dead: Option<DeadKey>,
+}
+
+impl TypingTracker {
+    pub fn set_private_apps(&mut self, apps: &[String]) {
+        self.private_apps = apps.iter().map(|app| app.to_lowercase()).collect();
+    }
+
+    /// Whether typed text would be hidden because of the privacy rules.
+    pub fn is_private(&self, app: Option<&str>) -> bool {
+        if secure_input_enabled() {
+            return true;
+        }
+        let Some(app) = app.map(str::to_lowercase) else {
+            return false;
+        };
+        self.private_apps
+            .iter()
+            .any(|private| app.contains(private.as_str()))
+    }
+
+    /// Returns a `TextEvent` for the key press of `key`, `held` being the
+    /// keys down including it and `platform_code` rdev's raw code, the
+    /// keysym on Linux.
+    pub fn feed(
+        &mut self,
+        key: Key,
+        unicode: Option<&UnicodeInfo>,
+        platform_code: u32,
+        held: &[String],
+        app: Option<&str>,
+    ) -> Option<InputEvent> {
+        if !self.enabled
+            || held
+                .iter()
+                .any(|k| SHORTCUT_MODIFIERS.contains(&k.as_str()))
+            || self.is_private(app)
+        {
+            self.pending_dead = None;
+            return None;
+        }
+
+        let (text, backspace) = match key {
+            Key::Backspace => {
+                self.pending_dead = None;
+                (String::new(), true)
+            }
+            Key::Return | Key::KpReturn => {
+                self.pending_dead = None;
+                ("\n".to_string(), false)
+            }
+            _ => {
+                let unicode = unicode?;
+                if unicode.is_dead {
+                    // X11 leaves composing to us, other platforms hand over
+                    // the accented letter with the next key
+                    self.pending_dead = if cfg!(target_os = "linux") {
+                        DeadKey::from_keysym(platform_code)
+                    } else {
+                        None
+                    };
+                    return None;
+                }
+                let text = text_of(unicode)?;
+                match self.pending_dead.take() {
+                    Some(dead) => (dead.compose(&text), false),
+                    None => (text, false),
+                }
+            }
+        };
+        Some(InputEvent::TextEvent {
+            key: format!("{:?}", key),
+            text,
+            backspace,
+        })
+    }
+}
+
+/// Printable text of a key press, `None` for control characters.
+fn text_of(unicode: &UnicodeInfo) -> Option<String> {
+    let text = match &unicode.name {
+        Some(name) => name.clone(),
+        None => String::from_utf16(&unicode.unicode).ok()?,
+    };
+    (!text.is_empty() && !text.chars().any(char::is_control)).then_some(text)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct DeadKey {
+    /// combining mark put after letters, e.g. U+0301 for é
+    combining: char,
+    /// the accent on its own, for dead key then space
+    spacing: char,
+}
+
+impl DeadKey {
+    /// X11 `dead_*` keysyms, from X11/keysymdef.h.
+    fn from_keysym(keysym: u32) -> Option<Self> {
+        let (combining, spacing) = match keysym {
+            0xfe50 => ('\u{300}', '`'),
+            0xfe51 => ('\u{301}', '´'),
+            0xfe52 => ('\u{302}', '^'),
+            0xfe53 => ('\u{303}', '~'),
+            0xfe54 => ('\u{304}', '¯'),
+            0xfe55 => ('\u{306}', '˘'),
+            0xfe56 => ('\u{307}', '˙'),
+            0xfe57 => ('\u{308}', '¨'),
+            0xfe58 => ('\u{30a}', '°'),
+            0xfe59 => ('\u{30b}', '˝'),
+            0xfe5a => ('\u{30c}', 'ˇ'),
+            0xfe5b => ('\u{327}', '¸'),
+            0xfe5c => ('\u{328}', '˛'),
+            _ => return None,
+        };
+        Some(Self { combining, spacing })
+    }
+
+    fn compose(self, text: &str) -> String {
+        match text.chars().next() {
+            Some(' ') => self.spacing.to_string(),
+            Some(c) if c.is_alphabetic() => {
+                format!("{}{}{}", c, self.combining, &text[c.len_utf8()..])
+            }
+            _ => format!("{}{}", self.spacing, text),
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn secure_input_enabled() -> bool {
+    #[link(name = "Carbon", kind = "framework")]
+    extern "C" {
+        fn IsSecureEventInputEnabled() -> u8;
+    }
+    // password fields and some terminals turn it on
+    unsafe { IsSecureEventInputEnabled() != 0 }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn secure_input_enabled() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn typed(tracker: &mut TypingTracker, key: Key, text: &str, held: &[&str]) -> Option<String> {
+        let unicode = UnicodeInfo {
+            name: Some(text.to_string()),
+            unicode: vec![],
+            is_dead: false,
+        };
+        let held: Vec<String> = held.iter().map(|k| k.to_string()).collect();
+        match tracker.feed(key, Some(&unicode), 0, &held, Some("Code")) {
+            Some(InputEvent::TextEvent {
+                text,
+                backspace: false,
+                ..
+            }) => Some(text),
+            Some(InputEvent::TextEvent {
+                backspace: true, ..
+            }) => Some("⌫".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn types_text_but_not_shortcuts() {
+        let mut tracker = TypingTracker {
+            enabled: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            typed(&mut tracker, Key::KeyA, "a", &["KeyA"]).as_deref(),
+            Some("a")
+        );
+        assert_eq!(
+            typed(&mut tracker, Key::KeyA, "A", &["ShiftLeft", "KeyA"]).as_deref(),
+            Some("A")
+        );
+        assert_eq!(
+            typed(&mut tracker, Key::KeyC, "c", &["ControlLeft", "KeyC"]),
+            None
+        );
+        assert_eq!(typed(&mut tracker, Key::Tab, "\t", &["Tab"]), None);
+        assert_eq!(
+            typed(&mut tracker, Key::Backspace, "", &["Backspace"]).as_deref(),
+            Some("⌫")
+        );
+
+        tracker.set_private_apps(&["code".to_string()]);
+        assert_eq!(typed(&mut tracker, Key::KeyA, "a", &["KeyA"]), None);
+    }
+
+    #[test]
+    fn types_dead_key_then_letter() {
+        let mut tracker = TypingTracker {
+            enabled: true,
+            ..Default::default()
+        };
+        let dead = UnicodeInfo {
+            name: None,
+            unicode: vec![],
+            is_dead: true,
+        };
+        // dead_acute, as rdev reports it on X11
+        assert!(tracker
+            .feed(
+                Key::Quote,
+                Some(&dead),
+                0xfe51,
+                &["Quote".to_string()],
+                Some("Code")
+            )
+            .is_none());
+        let expected = if cfg!(target_os = "linux") {
+            "e\u{301}"
+        } else {
+            "e"
+        };
+        assert_eq!(
+            typed(&mut tracker, Key::KeyE, "e", &["KeyE"]).as_deref(),
+            Some(expected)
+        );
+        assert_eq!(
+            typed(&mut tracker, Key::KeyE, "e", &["KeyE"]).as_deref(),
+            Some("e")
+        );
+    }
+
+    #[test]
+    fn composes_dead_keys() {
+        let acute = DeadKey::from_keysym(0xfe51).unwrap();
+        assert_eq!(acute.compose("e"), "e\u{301}");
+        assert_eq!(acute.compose(" "), "´");
+        assert_eq!(acute.compose("1"), "´1");
+        assert_eq!(DeadKey::from_keysym(0x61), None);
+    }
+}
@@ -1,3 +1,10 @@
+use serde_json::{json, Value};
+use tauri::{Emitter, Manager};
+
+use crate::app::state::AppState;
+use crate::app::store::{patch_store_value, set_store_value, KEY_STYLE_STORE};
+use crate::app::theme::{Alignment, Appearance};
+
 pub fn config_window(window: &tauri::WebviewWindow) {
     window
         .set_ignore_cursor_events(true)
@@ -50,3 +57,68 @@ pub fn config_window(window: &tauri::WebviewWindow) {
 
     window.show().expect("Failed to show window");
 }
+
+/// Opens the settings window, or focuses it when it's already open.
+pub fn open_settings_window(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window("settings") {
+        let _ = window.set_focus();
+        return;
+    }
+    let webview_url = tauri::WebviewUrl::App("index.html#/settings".into());
+    tauri::WebviewWindowBuilder::new(app, "settings", webview_url)
+        .title("Keyviz")
+        .inner_size(800.0, 640.0)
+        .min_inner_size(640.0, 480.0)
+        .max_inner_size(1000.0, 800.0)
+        .maximizable(false)
+        .build()
+        .unwrap();
+
+    app.emit_to("main", "settings-window", true).unwrap();
+}
+
+/// Lets the overlay take the cursor so the keystrokes can be dragged into
+/// place, click-through otherwise.
+pub fn set_interactive(app_state: &mut AppState, app: &tauri::AppHandle, interactive: bool) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    if let Err(e) = window.set_ignore_cursor_events(!interactive) {
+        eprintln!("Failed to set ignore cursor events: {}", e);
+        return;
+    }
+    app_state.interactive = interactive;
+    if interactive {
+        println!("✋ Drag the keystrokes into place");
+    }
+    if let Err(e) = app.emit_to("main", "interactive-mode", interactive) {
+        eprintln!("Failed to emit interactive mode: {}", e);
+    }
+}
+
+/// Saves where the keystrokes were dropped into the appearance settings.
+pub fn save_overlay_placement(
+    app: &tauri::AppHandle,
+    alignment: Alignment,
+    margin_x: f64,
+    margin_y: f64,
+) -> Result<(), String> {
+    let margin = |margin: f64| margin.round().clamp(0.0, 10_000.0);
+    let placement = json!({
+        "alignment": alignment,
+        "marginX": margin(margin_x),
+        "marginY": margin(margin_y),
+    });
+    patch_store_value(app, KEY_STYLE_STORE, "appearance", placement.clone()).or_else(|_| {
+        // never saved, start from the defaults
+        let mut appearance = serde_json::to_value(Appearance::default()).unwrap();
+        appearance["monitor"] = Value::Null;
+        appearance["spanMonitors"] = Value::Bool(false);
+        if let (Some(appearance), Some(placement)) =
+            (appearance.as_object_mut(), placement.as_object())
+        {
+            appearance.extend(placement.clone());
+        }
+        set_store_value(app, KEY_STYLE_STORE, "appearance", appearance)
+    })
+}
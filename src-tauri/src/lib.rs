@@ -1,23 +1,67 @@
 use std::sync::Mutex;
 
 use tauri::{
-    image::Image,
-    include_image,
-    menu::{Menu, MenuItem},
-    tray::TrayIconBuilder,
-    Emitter, Manager, WebviewWindowBuilder,
+    image::Image, include_image, menu::MenuItem, tray::TrayIconBuilder, Emitter, Manager, Wry,
 };
 
 mod app;
-use app::commands::{log, set_main_window_monitor, set_toggle_shortcut};
+use app::activation::start_activation_watcher;
+use app::cli::{parse_args, parse_instance_args, CliCommand};
+use app::commands::{
+    clear_statistics, diagnose, export_statistics, export_theme, get_launch_at_login,
+    get_listener_metrics, get_listener_status, get_statistics, get_theme_assets, import_theme, log,
+    resync, save_profile, set_activation_rules, set_annotations_enabled, set_arrange_shortcut,
+    set_follow_cursor, set_idle, set_interactive_mode, set_key_repeats, set_launch_at_login,
+    set_main_window_monitor, set_mouse_move_rate, set_overlay_placement, set_sequences,
+    set_span_monitors, set_statistics_enabled, set_stuck_key_timeout, set_toggle_shortcut,
+    set_typing_mode,
+};
+use app::control::{execute, run_client};
+use app::diagnose::diagnose as diagnose_environment;
 use app::event::start_listener;
+use app::foreground::start_foreground_watcher;
+use app::headless::run_headless;
+use app::idle::start_idle_watcher;
+use app::monitor::{
+    remember_monitor, start_monitor_watcher, track_main_window_monitor, MonitorInfo,
+};
 use app::state::AppState;
+use app::stats::start_stats_worker;
+use app::tray::{handle_menu_event, start_tray_sync, TrayMenu, TRAY_ID};
 use app::window::config_window;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    match parse_args(std::env::args()) {
+        CliCommand::Diagnose { json } => {
+            let report = diagnose_environment();
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report).unwrap());
+            } else {
+                print!("{}", report);
+            }
+            return;
+        }
+        CliCommand::Headless { json } => {
+            run_headless(json);
+            return;
+        }
+        CliCommand::Control(args) => std::process::exit(run_client(&args)),
+        CliCommand::Run => {}
+    }
+
     tauri::Builder::default()
-        .plugin(tauri_plugin_single_instance::init(|_, __, ___| {}))
+        .plugin(tauri_plugin_single_instance::init(|app, args, _| {
+            // e.g. `keyviz --toggle` bound to a window manager shortcut
+            let Some(toggle_item) = app.try_state::<MenuItem<Wry>>() else {
+                return;
+            };
+            for request in parse_instance_args(args) {
+                if let Err(e) = execute(app, &toggle_item, request) {
+                    eprintln!("{}", e);
+                }
+            }
+        }))
         .plugin(tauri_plugin_prevent_default::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
@@ -31,71 +75,98 @@ pub fn run() {
             }
 
             let app_handle = app.handle();
+            let mut app_state = AppState::new(&app_handle);
+            // mouse coordinates are relative to where the overlay starts out
+            if let Some(monitor) = app
+                .get_webview_window("main")
+                .and_then(|window| window.current_monitor().ok().flatten())
+            {
+                remember_monitor(&mut app_state, &MonitorInfo::from(&monitor));
+            }
             // manage app state
-            app.manage(Mutex::new(AppState::new(&app_handle)));
+            app.manage(Mutex::new(app_state));
 
             // tray actions
             let toggle_item = MenuItem::with_id(app, "toggle", "Stop", true, None::<&str>)?;
-            let settings_item = MenuItem::with_id(app, "settings", "Settings", true, None::<&str>)?;
-            let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+
+            // for requests forwarded by a second instance
+            app.manage(toggle_item.clone());
+            app.manage(TrayMenu::new(toggle_item.clone()));
 
             // start global input listener
             start_listener(app_handle.clone(), toggle_item.clone());
+            // keep the overlay on screen across monitor changes
+            start_monitor_watcher(app_handle.clone());
+            start_foreground_watcher(app_handle.clone());
+            start_stats_worker(app_handle.clone());
+            start_idle_watcher(app_handle.clone(), toggle_item.clone());
+            start_activation_watcher(app_handle.clone(), toggle_item.clone());
+            // let scripts drive keyviz, see `keyviz ctl`
+            #[cfg(unix)]
+            app::control::start_control_server(app_handle.clone(), toggle_item.clone());
 
-            // setup tray menu
-            let menu = Menu::with_items(app, &[&toggle_item, &settings_item, &quit_item])?;
-            let _ = TrayIconBuilder::with_id("keyviz-tray")
+            // setup tray, the menu follows the settings
+            let _ = TrayIconBuilder::with_id(TRAY_ID)
                 .icon(Image::from(include_image!("icons/tray.png")))
-                .menu(&menu)
                 .show_menu_on_left_click(true)
-                .on_menu_event(move |app, event| match event.id.as_ref() {
-                    "toggle" => {
-                        let state = app.state::<Mutex<AppState>>();
-                        let mut app_state = state.lock().unwrap();
-                        app_state.toggle_listener(app, &toggle_item);
-                    }
-                    "settings" => {
-                        if let Some(window) = app.get_webview_window("settings") {
-                            let _ = window.set_focus();
-                            return;
-                        }
-                        let webview_url = tauri::WebviewUrl::App("index.html#/settings".into());
-                        WebviewWindowBuilder::new(app, "settings", webview_url.clone())
-                            .title("Keyviz")
-                            .inner_size(800.0, 640.0)
-                            .min_inner_size(640.0, 480.0)
-                            .max_inner_size(1000.0, 800.0)
-                            .maximizable(false)
-                            .build()
-                            .unwrap();
-
-                        app.emit_to("main", "settings-window", true).unwrap();
-                    }
-                    "quit" => std::process::exit(0),
-                    _ => println!("um... what?"),
-                })
+                .on_menu_event(handle_menu_event)
                 .build(app);
+            start_tray_sync(app_handle);
+
+            // flags given to the first instance too, e.g. `--stop` from the login entry
+            let toggle_item = app.state::<MenuItem<Wry>>();
+            for request in parse_instance_args(std::env::args()) {
+                if let Err(e) = execute(app_handle, &toggle_item, request) {
+                    eprintln!("{}", e);
+                }
+            }
 
             Ok(())
         })
-        .on_window_event(|window, event| {
-            if window.label() != "settings" {
-                return;
-            }
-            match event {
-                tauri::WindowEvent::CloseRequested { .. } => {
-                    window
-                        .app_handle()
-                        .emit_to("main", "settings-window", false)
-                        .unwrap();
-                }
-                _ => {}
+        .on_window_event(|window, event| match (window.label(), event) {
+            ("settings", tauri::WindowEvent::CloseRequested { .. }) => {
+                window
+                    .app_handle()
+                    .emit_to("main", "settings-window", false)
+                    .unwrap();
             }
+            (
+                "main",
+                tauri::WindowEvent::Moved(_) | tauri::WindowEvent::ScaleFactorChanged { .. },
+            ) => track_main_window_monitor(window.app_handle()),
+            _ => {}
         })
         .invoke_handler(tauri::generate_handler![
             log,
+            diagnose,
             set_toggle_shortcut,
-            set_main_window_monitor
+            set_arrange_shortcut,
+            set_interactive_mode,
+            set_overlay_placement,
+            set_main_window_monitor,
+            set_follow_cursor,
+            set_span_monitors,
+            set_mouse_move_rate,
+            set_stuck_key_timeout,
+            get_listener_metrics,
+            get_listener_status,
+            resync,
+            set_annotations_enabled,
+            set_sequences,
+            set_typing_mode,
+            set_key_repeats,
+            set_idle,
+            set_activation_rules,
+            get_launch_at_login,
+            set_launch_at_login,
+            set_statistics_enabled,
+            get_statistics,
+            export_statistics,
+            clear_statistics,
+            import_theme,
+            export_theme,
+            get_theme_assets,
+            save_profile
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
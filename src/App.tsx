@@ -14,6 +14,7 @@ function App() {
       <Suspense fallback={<div>Loading...</div>}>
         <Routes>
           <Route path="/" element={<Visualization />} />
+          <Route path="/overlay" element={<Visualization mouseOnly />} />
           <Route path="/settings" element={
             <ThemeProvider>
               <Settings />
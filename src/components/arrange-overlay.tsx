@@ -0,0 +1,81 @@
+import { AppearanceSettings, useKeyStyle } from "@/stores/key_style";
+import { Alignment, alignmentForRow } from "@/types/style";
+import { invoke } from "@tauri-apps/api/core";
+import { useRef, useState } from "react";
+
+const HORIZONTAL = ["left", "center", "right"] as const;
+const VERTICAL = ["top", "center", "bottom"] as const;
+
+// which third of the screen a position falls in
+const third = (position: number, size: number) => Math.min(2, Math.max(0, Math.floor(position / size * 3)));
+
+// alignment and margins that put the keystrokes where the box was dropped
+const placementOf = (rect: DOMRect, appearance: AppearanceSettings) => {
+    const { innerWidth: width, innerHeight: height } = window;
+    const horizontal = HORIZONTAL[third(rect.left + rect.width / 2, width)];
+    let vertical = VERTICAL[third(rect.top + rect.height / 2, height)];
+    // the dead center isn't offered in the settings either
+    if (horizontal === "center" && vertical === "center") {
+        vertical = rect.top + rect.height / 2 < height / 2 ? "top" : "bottom";
+    }
+    const alignment = (vertical === "center" ? `center-${horizontal}` : `${vertical}-${horizontal}`) as Alignment;
+
+    const marginX = horizontal === "left" ? rect.left
+        : horizontal === "right" ? width - rect.right
+        : appearance.marginX;
+    const marginY = vertical === "top" ? rect.top
+        : vertical === "bottom" ? height - rect.bottom
+        : appearance.marginY;
+    return { alignment, marginX: Math.max(0, marginX), marginY: Math.max(0, marginY) };
+}
+
+// shown while the overlay takes the cursor, the box stands in for the keystrokes
+export const ArrangeOverlay = () => {
+    const appearance = useKeyStyle(state => state.appearance);
+    const text = useKeyStyle(state => state.text);
+    const boxRef = useRef<HTMLDivElement>(null);
+    const [drag, setDrag] = useState<{ x: number, y: number, dx: number, dy: number } | null>(null);
+
+    const onPointerUp = async () => {
+        const rect = boxRef.current?.getBoundingClientRect();
+        if (!drag || !rect) return;
+        try {
+            // saved by the backend, which makes the overlay click-through again
+            await invoke("set_overlay_placement", placementOf(rect, appearance));
+        } catch (error) {
+            console.error("Failed to save placement:", error);
+        }
+        setDrag(null);
+    }
+
+    return <div
+        className="absolute inset-0 flex bg-black/30"
+        style={{
+            paddingBlock: appearance.marginY,
+            paddingInline: appearance.marginX,
+            ...alignmentForRow[appearance.alignment],
+        }}
+    >
+        <div
+            ref={boxRef}
+            className="flex items-center justify-center rounded-xl border-2 border-dashed border-white bg-white/10 text-white font-medium select-none cursor-grab active:cursor-grabbing"
+            style={{
+                width: text.size * 6,
+                height: text.size * 2,
+                fontSize: text.size * 0.4,
+                transform: drag ? `translate(${drag.dx}px, ${drag.dy}px)` : undefined,
+            }}
+            onPointerDown={(event) => {
+                event.currentTarget.setPointerCapture(event.pointerId);
+                setDrag({ x: event.clientX, y: event.clientY, dx: 0, dy: 0 });
+            }}
+            onPointerMove={(event) => {
+                if (!drag) return;
+                setDrag({ ...drag, dx: event.clientX - drag.x, dy: event.clientY - drag.y });
+            }}
+            onPointerUp={onPointerUp}
+        >
+            Drag to move
+        </div>
+    </div>;
+}
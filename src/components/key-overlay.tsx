@@ -1,12 +1,28 @@
 import { easeInQuint, easeOutQuint } from "@/lib/utils";
 import { useKeyEvent } from "@/stores/key_event";
 import { useKeyStyle } from "@/stores/key_style";
+import { THEME_FONT, useThemeAssets } from "@/stores/theme_assets";
+import { KeyEvent } from "@/types/event";
 import { alignmentForColumn, alignmentForRow } from "@/types/style";
 import { AnimatePresence, motion, Variants } from "motion/react";
 import { useMemo } from "react";
 import { Keycap } from "./keycaps";
+import { TypingTicker } from "./typing-ticker";
 
 
+// shorter holds are just taps
+const HOLD_THRESHOLD_MS = 300;
+
+// e.g. "1.2s ×14", how long the keys were held and how often they repeated
+const keyTiming = (keys: KeyEvent[]) => {
+    const held = Math.max(0, ...keys.map(key => key.heldMs ?? 0));
+    const repeats = Math.max(0, ...keys.map(key => key.repeatCount));
+    const parts = [];
+    if (held >= HOLD_THRESHOLD_MS) parts.push(`${(held / 1000).toFixed(1)}s`);
+    if (repeats > 0) parts.push(`×${repeats}`);
+    return parts.join(" ");
+}
+
 const fadeVariants: Variants = {
     visible: { opacity: 1 },
     hidden: { opacity: 0 },
@@ -16,11 +32,14 @@ export const KeyOverlay = () => {
     const pressedKeys = useKeyEvent(state => state.pressedKeys);
     const groups = useKeyEvent(state => state.groups);
     const showHistory = useKeyEvent(state => state.showEventHistory);
+    const showKeyTiming = useKeyEvent(state => state.showKeyTiming);
 
     const appearance = useKeyStyle(state => state.appearance);
     const text = useKeyStyle(state => state.text);
     const border = useKeyStyle(state => state.border);
     const background = useKeyStyle(state => state.background);
+    const modifier = useKeyStyle(state => state.modifier);
+    const themeFont = useThemeAssets(state => state.font);
 
     const alignment = appearance.flexDirection === "row"
         ? alignmentForRow[appearance.alignment]
@@ -33,6 +52,7 @@ export const KeyOverlay = () => {
         alignItems: alignment.alignItems,
         justifyContent: alignment.justifyContent,
         gap: text.size * 0.5,
+        fontFamily: themeFont ? THEME_FONT : undefined,
     };
 
     const groupStyle = {
@@ -46,6 +66,15 @@ export const KeyOverlay = () => {
         }),
     }
 
+    // what the combo does, next to its keys
+    const descriptionStyle = {
+        alignSelf: "center",
+        paddingInline: text.size * 0.3,
+        fontSize: text.size * 0.6,
+        color: background.enabled ? text.color : modifier.color,
+        whiteSpace: "nowrap",
+    } as const;
+
     const variants = useMemo<Variants>(() => {
         switch (appearance.animation) {
             case "none":
@@ -90,8 +119,11 @@ export const KeyOverlay = () => {
                                 isPressed={groups.length - 1 === groupIndex && event.in(pressedKeys)}
                             />
                         ))}
+                        {group.description && <span style={descriptionStyle}>{group.description}</span>}
+                        {showKeyTiming && keyTiming(group.keys) && <span style={descriptionStyle}>{keyTiming(group.keys)}</span>}
                     </div>
                 ))}
+                <TypingTicker />
             </div>
         );
     }
@@ -137,9 +169,12 @@ export const KeyOverlay = () => {
                                 </motion.div>
                             ))}
                         </AnimatePresence>
+                        {group.description && <span style={descriptionStyle}>{group.description}</span>}
+                        {showKeyTiming && keyTiming(group.keys) && <span style={descriptionStyle}>{keyTiming(group.keys)}</span>}
                     </motion.div>
                 ))}
             </AnimatePresence>
+            <TypingTicker />
         </div>
     );
 };
\ No newline at end of file
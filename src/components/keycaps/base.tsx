@@ -1,13 +1,16 @@
 import { keymaps } from "@/lib/keymaps";
 import { useKeyStyle } from "@/stores/key_style";
+import { useThemeAssets } from "@/stores/theme_assets";
 import { KeyEvent } from "@/types/event";
 import { alignmentForRow } from "@/types/style";
+import { KeyIcon } from "./key-icon";
 
 export const KeycapBase = ({ event }: { event: KeyEvent }) => {
   const text = useKeyStyle((state) => state.text);
   const layout = useKeyStyle((state) => state.layout);
   const modifier = useKeyStyle((state) => state.modifier);
   const display = keymaps[event.name];
+  const hasThemeIcon = useThemeAssets((state) => event.name in state.icons);
 
   const textColor = event.isModifier() && modifier.highlight ? modifier.textColor : text.color;
   const textStyle: React.CSSProperties = {
@@ -24,14 +27,13 @@ export const KeycapBase = ({ event }: { event: KeyEvent }) => {
   const flexAlignment = alignmentForRow[text.alignment];
 
   // ───────────── With Icon ─────────────
-  if (layout.showIcon && display.icon) {
-    const Icon = display.icon;
+  if (layout.showIcon && (display.icon || hasThemeIcon)) {
     if (text.variant === "icon" || event.isArrow()) {
       return <div 
         className="w-full h-full flex"
         style={{ alignItems: flexAlignment.alignItems, justifyContent: flexAlignment.justifyContent }}
       >
-        <Icon color={textColor} size={text.size * 0.8} />
+        <KeyIcon name={event.name} icon={display.icon} color={textColor} size={text.size * 0.8} />
       </div>;
     } else {
       const alignItems = event.isModifier()
@@ -42,7 +44,7 @@ export const KeycapBase = ({ event }: { event: KeyEvent }) => {
         className="w-full h-full flex flex-col justify-between"
         style={{ alignItems }}
       >
-        <Icon color={textColor} size={text.size * 0.5} />
+        <KeyIcon name={event.name} icon={display.icon} color={textColor} size={text.size * 0.5} />
         <div style={{ ...textStyle, fontSize: text.size * 0.5 }}>
           {label}
         </div>
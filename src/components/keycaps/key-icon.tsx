@@ -0,0 +1,20 @@
+import { useThemeAssets } from "@/stores/theme_assets";
+import { LucideIcon } from "lucide-react";
+
+interface KeyIconProps {
+    name: string;
+    icon?: LucideIcon;
+    color: string;
+    size: number;
+}
+
+// the imported theme's icon for the key if any, tinted like the built-in ones
+export const KeyIcon = ({ name, icon: Icon, color, size }: KeyIconProps) => {
+    const src = useThemeAssets((state) => state.icons[name]);
+
+    if (src) {
+        const mask = `url("${src}") center / contain no-repeat`;
+        return <div style={{ width: size, height: size, backgroundColor: color, mask, WebkitMask: mask }} />;
+    }
+    return Icon ? <Icon color={color} size={size} /> : null;
+};
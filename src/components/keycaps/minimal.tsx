@@ -1,8 +1,10 @@
 import { keymaps } from "@/lib/keymaps";
 import { easeInOutExpo } from "@/lib/utils";
 import { useKeyStyle } from "@/stores/key_style";
+import { useThemeAssets } from "@/stores/theme_assets";
 import { motion } from "motion/react";
 import type { KeycapProps } from ".";
+import { KeyIcon } from "./key-icon";
 
 export const MinimalKeycap = ({ event, isPressed }: KeycapProps) => {
     const text = useKeyStyle((state) => state.text);
@@ -10,6 +12,7 @@ export const MinimalKeycap = ({ event, isPressed }: KeycapProps) => {
     const layout = useKeyStyle((state) => state.layout);
 
     const display = keymaps[event.name];
+    const hasThemeIcon = useThemeAssets((state) => event.name in state.icons);
     const color = event.isModifier() && modifier.highlight ? modifier.textColor : text.color;
     const textStyle: React.CSSProperties = {
         color,
@@ -22,13 +25,12 @@ export const MinimalKeycap = ({ event, isPressed }: KeycapProps) => {
     const label = display?.shortLabel ?? display.label;
     let child = <>{label}</>;
 
-    if (event.isModifier() && layout.showIcon && display.icon) {
-        const Icon = display.icon;
+    if (event.isModifier() && layout.showIcon && (display.icon || hasThemeIcon)) {
         if (text.variant === "icon" || event.isArrow()) {
-            child = <Icon color={color} size={text.size} />;
+            child = <KeyIcon name={event.name} icon={display.icon} color={color} size={text.size} />;
         } else {
             child = <>
-                <Icon color={color} size={text.size} />
+                <KeyIcon name={event.name} icon={display.icon} color={color} size={text.size} />
                 <div style={{ ...textStyle }}>
                     {text.variant === "text" ? display.label : label}
                 </div>
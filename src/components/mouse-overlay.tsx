@@ -4,10 +4,8 @@ import { useKeyStyle } from "@/stores/key_style";
 import { motion } from "motion/react";
 import { useEffect, useRef, useState } from "react";
 import { MouseIndicator } from "./mouse-indicator";
-import { platform } from "@tauri-apps/plugin-os";
 
 const MIN_CLICK_DISPLAY_MS = 200;
-const isMacos = platform() === "macos";
 
 export const MouseOverlay = () => {
     const wheel = useKeyEvent(state => state.mouse.wheel);
@@ -74,9 +72,9 @@ export const MouseOverlay = () => {
 
             if (!shouldUpdatePosition) return;
 
-            const dpr = isMacos ? 1 : window.devicePixelRatio || 1;
+            // the backend already sends overlay-relative logical pixels
             el.style.transform =
-                `translate3d(${state.mouse.x / dpr}px, ${state.mouse.y / dpr}px, 0) translate(-50%, -50%)`;
+                `translate3d(${state.mouse.x}px, ${state.mouse.y}px, 0) translate(-50%, -50%)`;
         });
 
         return () => unsubscribe();
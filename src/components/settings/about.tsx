@@ -1,7 +1,9 @@
 import { Button } from "@/components/ui/button"
 import { Item, ItemActions, ItemContent, ItemDescription, ItemTitle } from "@/components/ui/item"
-import { DiscordIcon, GithubIcon, LinkSquare02Icon, SparklesIcon, StarsIcon } from "@hugeicons/core-free-icons"
+import { DiagnosticReport, ListenerMetrics } from "@/types/diagnose"
+import { Copy01Icon, DiscordIcon, GithubIcon, LinkSquare02Icon, SparklesIcon, StarsIcon, Bug01Icon } from "@hugeicons/core-free-icons"
 import { HugeiconsIcon } from "@hugeicons/react"
+import { invoke } from "@tauri-apps/api/core"
 import { openUrl } from "@tauri-apps/plugin-opener"
 import { motion } from "motion/react"
 import { useState } from "react"
@@ -13,6 +15,8 @@ export const AboutPage = () => {
     const [checking, setChecking] = useState(false);
     const [updateAvailable, setUpdateAvailable] = useState(false);
     const [hovered, setHovered] = useState(false);
+    const [report, setReport] = useState<DiagnosticReport | null>(null);
+    const [metrics, setMetrics] = useState<ListenerMetrics | null>(null);
 
     const visitReleasePage = () => {
         openUrl('https://github.com/mulaRahul/keyviz/releases');
@@ -41,6 +45,21 @@ export const AboutPage = () => {
         setChecking(false);
     }
 
+    const runDiagnostics = async () => {
+        try {
+            setReport(await invoke<DiagnosticReport>("diagnose"));
+            setMetrics(await invoke<ListenerMetrics>("get_listener_metrics"));
+        } catch (error) {
+            toast.error("Failed to run diagnostics.");
+        }
+    }
+
+    const copyReport = async () => {
+        if (!report) return;
+        await navigator.clipboard.writeText(JSON.stringify({ ...report, metrics }, null, 2));
+        toast.success("Diagnostics copied to clipboard.");
+    }
+
     return <div>
         <div className="py-6 flex flex-col items-center bg-linear-to-b from-secondary to-background">
             <div className="relative w-24 h-24">
@@ -137,6 +156,40 @@ export const AboutPage = () => {
                 </ItemActions>
             </Item>
 
+            <Item variant="muted" className="transition-all peer-hover:blur-xs">
+                <ItemContent>
+                    <ItemTitle>
+                        <HugeiconsIcon icon={Bug01Icon} size="1em" /> Diagnostics
+                    </ItemTitle>
+                    <ItemDescription className="max-w-100">
+                        {
+                            report
+                                ? `${report.os}, ${report.display_server}, ${report.backend}`
+                                : "Check why keystrokes aren't showing up."
+                        }
+                    </ItemDescription>
+                    {
+                        report && <ul className="mt-2 text-xs font-mono">
+                            {report.checks.map((check) => (
+                                <li key={check.name} className={check.status === "ok" ? "text-muted-foreground" : check.status === "warn" ? "text-yellow-600" : "text-red-600"}>
+                                    [{check.status}] {check.name}: {check.detail}
+                                </li>
+                            ))}
+                            {metrics && <li className="text-muted-foreground">
+                                [info] events: {metrics.events_queued} received, {metrics.events_overflowed} lost;
+                                mouse moves: {metrics.mouse_moves_emitted} sent, {metrics.mouse_moves_dropped} coalesced
+                            </li>}
+                        </ul>
+                    }
+                </ItemContent>
+                <ItemActions>
+                    {report && <Button variant="outline" size="icon" onClick={copyReport}>
+                        <HugeiconsIcon icon={Copy01Icon} />
+                    </Button>}
+                    <Button variant="outline" onClick={runDiagnostics}>Run</Button>
+                </ItemActions>
+            </Item>
+
             <Item variant="muted" className="transition-all peer-hover:blur-xs">
                 <ItemContent>
                     <ItemTitle>
@@ -1,15 +1,19 @@
 import { useEffect, useState } from "react";
 
 import { AlignmentSelector } from "@/components/ui/alignment-selector";
+import { Button } from "@/components/ui/button";
 import { Item, ItemActions, ItemContent, ItemDescription, ItemTitle } from "@/components/ui/item";
 import { NumberInput } from "@/components/ui/number-input";
 import { NumberScrubber } from "@/components/ui/number-input-scrub";
 import { Select, SelectContent, SelectGroup, SelectItem, SelectTrigger, SelectValue } from "@/components/ui/select";
+import { Switch } from "@/components/ui/switch";
 import { Toggle } from "@/components/ui/toggle";
 import { useKeyEvent } from "@/stores/key_event";
-import { useKeyStyle } from "@/stores/key_style";
-import { ComputerIcon, KeyframesDoubleIcon, KeyframesDoubleRemoveIcon, Link02Icon, ParagraphSpacingIcon, TextAlignLeftIcon, Time03Icon, Unlink02Icon } from "@hugeicons/core-free-icons";
+import { FOLLOW_CURSOR, useKeyStyle } from "@/stores/key_style";
+import { ComputerIcon, Drag03Icon, KeyframesDoubleIcon, KeyframesDoubleRemoveIcon, Link02Icon, ParagraphSpacingIcon, TextAlignLeftIcon, Time03Icon, Unlink02Icon } from "@hugeicons/core-free-icons";
 import { HugeiconsIcon } from "@hugeicons/react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { availableMonitors, Monitor } from "@tauri-apps/api/window";
 
 
@@ -30,6 +34,14 @@ export const AppearanceSettings = () => {
             }
             setMonitors(monitors);
         });
+
+        // displays plugged/unplugged or resolution changed
+        const unlisten = listen("monitors-changed", () => {
+            availableMonitors().then(setMonitors);
+        });
+        return () => {
+            unlisten.then(f => f());
+        };
     }, []);
 
     return <div className="flex flex-col gap-y-4 p-6">
@@ -37,7 +49,7 @@ export const AppearanceSettings = () => {
 
         <h2 className="text-sm text-muted-foreground font-medium">Position</h2>
         {
-            monitors.length > 1 &&
+            monitors.length > 1 && <>
             <Item variant="muted">
                 <ItemContent>
                     <ItemTitle>
@@ -60,6 +72,7 @@ export const AppearanceSettings = () => {
                         </SelectTrigger>
                         <SelectContent>
                             <SelectGroup>
+                                <SelectItem value={FOLLOW_CURSOR}>Follow Cursor</SelectItem>
                                 {
                                     monitors.map((monitor, index) => (
                                         <SelectItem key={monitor.name} value={monitor.name ?? index.toString()}>
@@ -72,6 +85,25 @@ export const AppearanceSettings = () => {
                     </Select>
                 </ItemActions>
             </Item>
+            <Item variant="muted">
+                <ItemContent>
+                    <ItemTitle>
+                        <HugeiconsIcon icon={ComputerIcon} size="1em" />
+                        Span Displays
+                    </ItemTitle>
+                    <ItemDescription>
+                        Show mouse clicks on every display, keystrokes stay on the selected one.
+                    </ItemDescription>
+                </ItemContent>
+                <ItemActions>
+                    <Switch
+                        checked={!!appearance.spanMonitors}
+                        disabled={appearance.monitor === FOLLOW_CURSOR}
+                        onCheckedChange={(spanMonitors) => setAppearance({ spanMonitors })}
+                    />
+                </ItemActions>
+            </Item>
+            </>
         }
 
         <Item variant="muted">
@@ -138,6 +170,22 @@ export const AppearanceSettings = () => {
             </ItemActions>
         </Item>
 
+        <Item variant="muted">
+            <ItemContent>
+                <ItemTitle>
+                    <HugeiconsIcon icon={Drag03Icon} size="1em" /> Drag to Position
+                </ItemTitle>
+                <ItemDescription>
+                    Move the keystrokes with the mouse, sets alignment and margin
+                </ItemDescription>
+            </ItemContent>
+            <ItemActions>
+                <Button variant="outline" size="sm" onClick={() => invoke("set_interactive_mode", { enabled: true })}>
+                    Arrange
+                </Button>
+            </ItemActions>
+        </Item>
+
         <h2 className="text-sm text-muted-foreground font-medium">Animation</h2>
         <Item variant="muted">
             <ItemContent>
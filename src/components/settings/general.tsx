@@ -1,4 +1,7 @@
 import { invoke } from '@tauri-apps/api/core';
+import { save } from '@tauri-apps/plugin-dialog';
+import { useEffect, useState } from 'react';
+import { toast } from 'sonner';
 
 import { ShortcutRecorder } from '@/components/shortcut-recorder';
 import { Button } from '@/components/ui/button';
@@ -10,17 +13,48 @@ import {
     DrawerTitle,
     DrawerTrigger,
 } from "@/components/ui/drawer";
+import { Input } from '@/components/ui/input';
 import { Item, ItemActions, ItemContent, ItemDescription, ItemHeader, ItemTitle } from "@/components/ui/item";
 import { NumberInput } from '@/components/ui/number-input';
 import { Switch } from "@/components/ui/switch";
+import { Textarea } from "@/components/ui/textarea";
 import { ToggleGroup, ToggleGroupItem } from '@/components/ui/toggle-group';
 import { cn } from "@/lib/utils";
-import { KeyEventState, useKeyEvent } from "@/stores/key_event";
+import { ActivationRules, KeyEventState, KeySequence, useKeyEvent } from "@/stores/key_event";
 import { KeyStyleState, useKeyStyle } from "@/stores/key_style";
-import { ArrowHorizontalIcon, ArrowVerticalIcon, FilterHorizontalIcon, FilterIcon, LayerIcon, ToggleOnIcon } from "@hugeicons/core-free-icons";
+import { Statistics } from "@/types/statistics";
+import { Analytics01Icon, ArrowHorizontalIcon, ArrowVerticalIcon, ComputerIcon, Link02Icon, CursorEdit01Icon, Delete02Icon, Download04Icon, Drag03Icon, KeyframesMultipleIcon, Note01Icon, FilterHorizontalIcon, FilterIcon, LayerIcon, Moon02Icon, Time03Icon, ToggleOnIcon } from "@hugeicons/core-free-icons";
 import { HugeiconsIcon } from "@hugeicons/react";
 import { CustomFilter } from '../custom-filter';
 
+interface LaunchAtLogin {
+    enabled: boolean;
+    startPaused: boolean;
+}
+
+// most used shortcuts of the last 30 days
+const topShortcuts = (statistics: Statistics, count = 5) => {
+    const totals: Record<string, number> = {};
+    for (const day of Object.values(statistics.days)) {
+        for (const [combo, presses] of Object.entries(day.combos)) {
+            totals[combo] = (totals[combo] ?? 0) + presses;
+        }
+    }
+    return Object.entries(totals).sort((a, b) => b[1] - a[1]).slice(0, count);
+}
+
+const splitLines = (text: string) => text.split("\n").map(line => line.trim()).filter(Boolean);
+
+// one sequence per line, "Ctrl+X Ctrl+S = Save Buffer"
+const formatSequences = (sequences: KeySequence[]) =>
+    sequences.map(({ keys, name }) => `${keys} = ${name}`).join("\n");
+
+const parseSequences = (text: string): KeySequence[] =>
+    text.split("\n").flatMap(line => {
+        // a space before "=" keeps "Ctrl+= = Zoom In" working
+        const match = line.trim().match(/^(.+?)\s+=\s*(.+)$/);
+        return match ? [{ keys: match[1], name: match[2] }] : [];
+    });
 
 export const GeneralSettings = () => {
     const {
@@ -28,12 +62,99 @@ export const GeneralSettings = () => {
         allowedKeys,
         showEventHistory, setShowEventHistory,
         maxHistory, setMaxHistory,
-        toggleShortcut, setToggleShortcut
+        toggleShortcut, setToggleShortcut,
+        arrangeShortcut, setArrangeShortcut,
+        showKeyTiming, setShowKeyTiming,
+        stuckKeyTimeoutSecs, setStuckKeyTimeoutSecs,
+        idleTimeoutSecs, setIdleTimeoutSecs,
+        hideWhenIdle, setHideWhenIdle,
+        activationRules, setActivationRules,
+        collectStatistics, setCollectStatistics,
+        showAnnotations, setShowAnnotations,
+        sequences, setSequences,
+        sequenceTimeoutMs, setSequenceTimeoutMs,
+        typingMode, setTypingMode,
+        privateApps, setPrivateApps,
     } = useKeyEvent();
+    const [sequenceText, setSequenceText] = useState(() => formatSequences(sequences));
+    const [privateAppsText, setPrivateAppsText] = useState(() => privateApps.join("\n"));
+
+    const updateTypingMode = (enabled: boolean, privateApps: string[]) => {
+        setTypingMode(enabled);
+        setPrivateApps(privateApps);
+        invoke('set_typing_mode', { enabled, privateApps });
+    }
+
+    const updateIdle = (timeoutSecs: number, hideWindows: boolean) => {
+        setIdleTimeoutSecs(timeoutSecs);
+        setHideWhenIdle(hideWindows);
+        invoke('set_idle', { timeoutSecs, hideWindows });
+    }
+
+    const [processText, setProcessText] = useState(() => activationRules.processes.join("\n"));
+    const [appText, setAppText] = useState(() => activationRules.apps.join("\n"));
+
+    const updateActivationRules = (changes: Partial<ActivationRules>) => {
+        const rules = { ...activationRules, ...changes };
+        setActivationRules(rules);
+        invoke('set_activation_rules', { rules });
+    }
+
+    const updateSequences = (sequences: KeySequence[], timeoutMs: number) => {
+        setSequences(sequences);
+        setSequenceTimeoutMs(timeoutMs);
+        invoke('set_sequences', { sequences, timeoutMs });
+    }
+    const [shortcuts, setShortcuts] = useState<[string, number][]>([]);
+    const [launchAtLogin, setLaunchAtLogin] = useState<LaunchAtLogin>({ enabled: false, startPaused: false });
+
+    // the login entry itself is the setting, read it back from the system
+    useEffect(() => {
+        invoke<LaunchAtLogin>('get_launch_at_login').then(setLaunchAtLogin);
+    }, []);
+
+    const updateLaunchAtLogin = async (setting: LaunchAtLogin) => {
+        try {
+            await invoke('set_launch_at_login', { setting });
+            setLaunchAtLogin(setting);
+        } catch (err) {
+            toast.error("Error updating launch at login", { description: String(err) });
+        }
+    }
 
     const direction = useKeyStyle(state => state.appearance.flexDirection);
     const setAppearance = useKeyStyle(state => state.setAppearance);
 
+    useEffect(() => {
+        if (!collectStatistics) return;
+        const from = new Date(Date.now() - 30 * 24 * 60 * 60 * 1000).toISOString().slice(0, 10);
+        invoke<Statistics>('get_statistics', { from }).then(stats => setShortcuts(topShortcuts(stats)));
+    }, [collectStatistics]);
+
+    const exportStatistics = async () => {
+        try {
+            const path = await save({
+                defaultPath: "keyviz_statistics.json",
+                filters: [{ name: "JSON Files", extensions: ["json"] }],
+            });
+            if (!path) return;
+            await invoke('export_statistics', { path });
+            toast.success("Exported successfully", { description: path });
+        } catch (err) {
+            toast.error("Error exporting statistics", { description: String(err) });
+        }
+    }
+
+    const clearStatistics = async () => {
+        try {
+            await invoke('clear_statistics');
+            setShortcuts([]);
+            toast.success("Statistics cleared");
+        } catch (err) {
+            toast.error("Error clearing statistics", { description: String(err) });
+        }
+    }
+
     return <div className="flex flex-col gap-y-4 p-6">
         <h1 className="text-xl font-semibold">General</h1>
 
@@ -128,6 +249,162 @@ export const GeneralSettings = () => {
             </Item>
         </div>
 
+        <Item variant="muted">
+            <ItemContent>
+                <ItemTitle>
+                    <HugeiconsIcon icon={Time03Icon} size="1em" /> Hold Duration
+                </ItemTitle>
+                <ItemDescription>
+                    Show how long keys were held and how often they auto-repeated
+                </ItemDescription>
+            </ItemContent>
+            <ItemActions>
+                <Switch checked={showKeyTiming} onCheckedChange={enabled => {
+                    setShowKeyTiming(enabled);
+                    invoke('set_key_repeats', { enabled });
+                }} />
+            </ItemActions>
+        </Item>
+
+        <Item variant="muted">
+            <ItemContent>
+                <ItemTitle>Release Stuck Keys</ItemTitle>
+                <ItemDescription>
+                    Release keys held longer than this many seconds, 0 to only release keys the system reports up
+                </ItemDescription>
+            </ItemContent>
+            <ItemActions className="max-w-24">
+                <NumberInput
+                    className="h-8"
+                    value={stuckKeyTimeoutSecs}
+                    onChange={seconds => {
+                        setStuckKeyTimeoutSecs(seconds);
+                        invoke('set_stuck_key_timeout', { seconds });
+                    }}
+                    minValue={0}
+                    maxValue={3600}
+                    step={10}
+                />
+            </ItemActions>
+        </Item>
+
+        <Item variant="muted">
+            <ItemContent>
+                <ItemTitle>
+                    <HugeiconsIcon icon={Link02Icon} size="1em" /> Launch at Login
+                </ItemTitle>
+                <ItemDescription>
+                    Start Keyviz when you log in
+                </ItemDescription>
+            </ItemContent>
+            <ItemActions>
+                <Switch checked={launchAtLogin.enabled} onCheckedChange={enabled => updateLaunchAtLogin({ ...launchAtLogin, enabled })} />
+            </ItemActions>
+        </Item>
+
+        <Item variant="muted" className={cn(launchAtLogin.enabled ? "" : "pointer-events-none opacity-50", "transition-opacity")}>
+            <ItemContent>
+                <ItemTitle>Start Paused</ItemTitle>
+                <ItemDescription>
+                    Wait for the toggle shortcut or tray before visualizing
+                </ItemDescription>
+            </ItemContent>
+            <ItemActions>
+                <Switch checked={launchAtLogin.startPaused} onCheckedChange={startPaused => updateLaunchAtLogin({ ...launchAtLogin, startPaused })} />
+            </ItemActions>
+        </Item>
+
+        <Item variant="muted">
+            <ItemContent>
+                <ItemTitle>
+                    <HugeiconsIcon icon={Moon02Icon} size="1em" /> Pause When Idle
+                </ItemTitle>
+                <ItemDescription>
+                    Pause after this many seconds without input and resume on the next one, 0 to never pause
+                </ItemDescription>
+            </ItemContent>
+            <ItemActions className="max-w-24">
+                <NumberInput
+                    className="h-8"
+                    value={idleTimeoutSecs}
+                    onChange={timeoutSecs => updateIdle(timeoutSecs, hideWhenIdle)}
+                    minValue={0}
+                    maxValue={3600}
+                    step={10}
+                />
+            </ItemActions>
+        </Item>
+
+        <Item variant="muted" className={cn(idleTimeoutSecs > 0 ? "" : "pointer-events-none opacity-50", "transition-opacity")}>
+            <ItemContent>
+                <ItemTitle>Hide When Idle</ItemTitle>
+                <ItemDescription>
+                    Hide the overlay entirely while paused, saving compositor work
+                </ItemDescription>
+            </ItemContent>
+            <ItemActions>
+                <Switch checked={hideWhenIdle} onCheckedChange={hide => updateIdle(idleTimeoutSecs, hide)} />
+            </ItemActions>
+        </Item>
+
+        <Item variant="muted">
+            <ItemContent>
+                <ItemTitle>
+                    <HugeiconsIcon icon={ComputerIcon} size="1em" /> Auto Start
+                </ItemTitle>
+                <ItemDescription>
+                    Only visualize while recording, in certain apps or at certain times
+                </ItemDescription>
+            </ItemContent>
+            <ItemActions>
+                <Switch checked={activationRules.enabled} onCheckedChange={enabled => updateActivationRules({ enabled })} />
+            </ItemActions>
+        </Item>
+
+        {
+            activationRules.enabled &&
+            <Item variant="muted">
+                <ItemContent className="gap-y-2">
+                    <ItemTitle>Screen Recorders</ItemTitle>
+                    <ItemDescription>
+                        Program name, then words its arguments must contain, one per line
+                    </ItemDescription>
+                    <Textarea
+                        className="font-mono"
+                        value={processText}
+                        onChange={event => setProcessText(event.target.value)}
+                        onBlur={() => updateActivationRules({ processes: splitLines(processText) })}
+                    />
+                    <ItemTitle>Apps</ItemTitle>
+                    <ItemDescription>
+                        While one of these apps is focused, one per line
+                    </ItemDescription>
+                    <Textarea
+                        placeholder="Code"
+                        value={appText}
+                        onChange={event => setAppText(event.target.value)}
+                        onBlur={() => updateActivationRules({ apps: splitLines(appText) })}
+                    />
+                    <div className="flex items-center justify-between gap-2 text-sm">
+                        <span>Between</span>
+                        <Input
+                            type="time"
+                            className="h-8 max-w-32"
+                            value={activationRules.from ?? ""}
+                            onChange={event => updateActivationRules({ from: event.target.value || undefined })}
+                        />
+                        <span>and</span>
+                        <Input
+                            type="time"
+                            className="h-8 max-w-32"
+                            value={activationRules.to ?? ""}
+                            onChange={event => updateActivationRules({ to: event.target.value || undefined })}
+                        />
+                    </div>
+                </ItemContent>
+            </Item>
+        }
+
         <Item variant="muted">
             <ItemHeader className="flex-col items-start">
                 <ItemTitle>
@@ -144,5 +421,153 @@ export const GeneralSettings = () => {
                 }} />
             </ItemContent>
         </Item>
+
+        <Item variant="muted">
+            <ItemHeader className="flex-col items-start">
+                <ItemTitle>
+                    <HugeiconsIcon icon={Drag03Icon} size="1em" /> Arrange Shortcut
+                </ItemTitle>
+                <ItemDescription>
+                    Drag the keystrokes to a new spot on screen, Escape cancels
+                </ItemDescription>
+            </ItemHeader>
+            <ItemContent>
+                <ShortcutRecorder value={arrangeShortcut} onChange={shortcut => {
+                    setArrangeShortcut(shortcut);
+                    invoke('set_arrange_shortcut', { shortcut });
+                }} />
+            </ItemContent>
+        </Item>
+
+        <Item variant="muted">
+            <ItemContent>
+                <ItemTitle>
+                    <HugeiconsIcon icon={Note01Icon} size="1em" /> Shortcut Descriptions
+                </ItemTitle>
+                <ItemDescription>
+                    Show what a combo does, e.g. Command Palette. Add your own in the config folder under annotations
+                </ItemDescription>
+            </ItemContent>
+            <ItemActions>
+                <Switch checked={showAnnotations} onCheckedChange={enabled => {
+                    setShowAnnotations(enabled);
+                    invoke('set_annotations_enabled', { enabled });
+                }} />
+            </ItemActions>
+        </Item>
+
+        <Item variant="muted">
+            <ItemContent>
+                <ItemTitle>
+                    <HugeiconsIcon icon={CursorEdit01Icon} size="1em" /> Typing Mode
+                </ItemTitle>
+                <ItemDescription>
+                    Show typed text as a ticker, shortcuts still show as keys
+                </ItemDescription>
+            </ItemContent>
+            <ItemActions>
+                <Switch checked={typingMode} onCheckedChange={enabled => updateTypingMode(enabled, privateApps)} />
+            </ItemActions>
+        </Item>
+
+        {
+            typingMode &&
+            <Item variant="muted">
+                <ItemHeader className="flex-col items-start">
+                    <ItemTitle>Private Apps</ItemTitle>
+                    <ItemDescription>
+                        Typed text is never shown while one of these apps is focused, one per line
+                    </ItemDescription>
+                </ItemHeader>
+                <ItemContent>
+                    <Textarea
+                        value={privateAppsText}
+                        onChange={event => setPrivateAppsText(event.target.value)}
+                        onBlur={() => updateTypingMode(
+                            typingMode,
+                            splitLines(privateAppsText),
+                        )}
+                    />
+                </ItemContent>
+            </Item>
+        }
+
+        <Item variant="muted">
+            <ItemHeader className="flex-col items-start">
+                <ItemTitle>
+                    <HugeiconsIcon icon={KeyframesMultipleIcon} size="1em" /> Key Sequences
+                </ItemTitle>
+                <ItemDescription>
+                    Name sequences like Vim's g g or Emacs' Ctrl+X Ctrl+S, one per line
+                </ItemDescription>
+            </ItemHeader>
+            <ItemContent className="gap-y-2">
+                <Textarea
+                    className="font-mono"
+                    placeholder={"g g = Go to Top\nCtrl+X Ctrl+S = Save Buffer"}
+                    value={sequenceText}
+                    onChange={event => setSequenceText(event.target.value)}
+                    onBlur={() => updateSequences(parseSequences(sequenceText), sequenceTimeoutMs)}
+                />
+                <div className="flex items-center justify-between text-sm">
+                    <span>Max pause between steps (ms)</span>
+                    <NumberInput
+                        className="h-8 max-w-28"
+                        value={sequenceTimeoutMs}
+                        onChange={timeoutMs => updateSequences(sequences, timeoutMs)}
+                        minValue={200}
+                        maxValue={5000}
+                        step={100}
+                    />
+                </div>
+            </ItemContent>
+        </Item>
+
+        <Item variant="muted">
+            <ItemContent>
+                <ItemTitle>
+                    <HugeiconsIcon icon={Analytics01Icon} size="1em" /> Statistics
+                </ItemTitle>
+                <ItemDescription>
+                    Count keys, shortcuts and clicks per day, kept only on this computer
+                </ItemDescription>
+            </ItemContent>
+            <ItemActions>
+                {
+                    collectStatistics && <>
+                        <Button variant="outline" size="icon-sm" onClick={exportStatistics}>
+                            <HugeiconsIcon icon={Download04Icon} />
+                        </Button>
+                        <Button variant="outline" size="icon-sm" onClick={clearStatistics}>
+                            <HugeiconsIcon icon={Delete02Icon} />
+                        </Button>
+                    </>
+                }
+                <Switch checked={collectStatistics} onCheckedChange={enabled => {
+                    setCollectStatistics(enabled);
+                    invoke('set_statistics_enabled', { enabled });
+                }} />
+            </ItemActions>
+        </Item>
+
+        {
+            collectStatistics && shortcuts.length > 0 &&
+            <Item variant="muted">
+                <ItemHeader className="flex-col items-start">
+                    <ItemTitle>Most Used Shortcuts</ItemTitle>
+                    <ItemDescription>Last 30 days</ItemDescription>
+                </ItemHeader>
+                <ItemContent>
+                    {
+                        shortcuts.map(([combo, presses]) => (
+                            <div key={combo} className="flex justify-between text-sm">
+                                <span className="font-mono">{combo}</span>
+                                <span className="text-muted-foreground">{presses}</span>
+                            </div>
+                        ))
+                    }
+                </ItemContent>
+            </Item>
+        }
     </div>;
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
 import { AlignmentSelector } from "@/components/ui/alignment-selector";
 import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
 import { ColorInput } from "@/components/ui/color-picker";
 import { Item, ItemActions, ItemContent, ItemDescription, ItemGrid, ItemGroup, ItemTitle } from "@/components/ui/item";
 import { Label } from "@/components/ui/label";
@@ -9,8 +10,11 @@ import { Slider } from "@/components/ui/slider";
 import { Switch } from "@/components/ui/switch";
 import { ToggleGroup, ToggleGroupItem } from "@/components/ui/toggle-group";
 import { KeyStyleState, useKeyStyle } from "@/stores/key_style";
-import { AlignHorizontalCenterIcon, AlignLeftIcon, AlignRightIcon, Download01Icon, PaintBoardIcon, Refresh01Icon, Upload01Icon } from "@hugeicons/core-free-icons";
+import { AlignHorizontalCenterIcon, AlignLeftIcon, AlignRightIcon, Download01Icon, FloppyDiskIcon, PaintBoardIcon, Refresh01Icon, Upload01Icon } from "@hugeicons/core-free-icons";
 import { HugeiconsIcon } from "@hugeicons/react";
+import { invoke } from "@tauri-apps/api/core";
+import { useState } from "react";
+import { toast } from "sonner";
 import { DropdownMenu, DropdownMenuContent, DropdownMenuGroup, DropdownMenuItem, DropdownMenuTrigger } from "../ui/dropdown-menu";
 import { Collapsible, CollapsibleContent, CollapsibleTrigger } from "../ui/collapsible";
 
@@ -133,6 +137,17 @@ export const KeycapSettings = () => {
     const importStyle = useKeyStyle(state => state.import);
     const exportStyle = useKeyStyle(state => state.export);
 
+    const [profileName, setProfileName] = useState("");
+    const saveProfile = async () => {
+        try {
+            await invoke("save_profile", { name: profileName });
+            toast.success("Profile saved", { description: profileName });
+            setProfileName("");
+        } catch (err) {
+            toast.error("Error saving profile", { description: String(err) });
+        }
+    }
+
     const onStyleChange = (value: string) => {
         if (value === "minimal") {
             setTextStyle({ variant: "icon" });
@@ -228,6 +243,27 @@ export const KeycapSettings = () => {
             </ItemActions>
         </Item>
 
+        <Item variant="muted">
+            <ItemContent>
+                <ItemTitle>Save as Profile</ItemTitle>
+                <ItemDescription>
+                    Switch between saved styles from the tray or with keyviz ctl profile
+                </ItemDescription>
+            </ItemContent>
+            <ItemActions>
+                <Input
+                    className="h-8 w-40"
+                    placeholder="Profile name"
+                    value={profileName}
+                    onChange={event => setProfileName(event.target.value)}
+                    onKeyDown={event => event.key === "Enter" && profileName.trim() && saveProfile()}
+                />
+                <Button variant="outline" size="sm" onClick={saveProfile} disabled={!profileName.trim()}>
+                    <HugeiconsIcon icon={FloppyDiskIcon} className="mr-2" /> Save
+                </Button>
+            </ItemActions>
+        </Item>
+
         <Collapsible defaultOpen={true}>
             <CollapsibleTrigger>
                 <h2 className="text-sm text-muted-foreground font-medium">Text</h2>
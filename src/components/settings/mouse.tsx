@@ -4,6 +4,7 @@ import { NumberInput } from "@/components/ui/number-input";
 import { Switch } from "@/components/ui/switch";
 import { useKeyEvent } from "@/stores/key_event";
 import { useKeyStyle } from '@/stores/key_style';
+import { invoke } from "@tauri-apps/api/core";
 import { ArrowExpand02Icon, Cursor01Icon, CursorCircleSelection01Icon, CursorEdit01Icon, CursorMagicSelection03FreeIcons, Drag03Icon, KeyboardIcon, Link02Icon, MouseLeftClick05Icon, PaintBoardIcon, Unlink02Icon } from "@hugeicons/core-free-icons";
 import { HugeiconsIcon } from "@hugeicons/react";
 import { NumberScrubber } from "../ui/number-input-scrub";
@@ -17,6 +18,8 @@ export const MouseSettings = () => {
 
     const dragThreshold = useKeyEvent(state => state.dragThreshold);
     const setDragThreshold = useKeyEvent(state => state.setDragThreshold);
+    const mouseMoveRate = useKeyEvent(state => state.mouseMoveRate);
+    const setMouseMoveRate = useKeyEvent(state => state.setMouseMoveRate);
 
     const [offsetLinked, setOffsetLinked] = useState(true);
 
@@ -203,5 +206,28 @@ export const MouseSettings = () => {
                 />
             </ItemActions>
         </Item>
+
+        <Item variant="muted">
+            <ItemContent>
+                <ItemTitle>
+                    <HugeiconsIcon icon={Cursor01Icon} size="1em" /> Move Rate
+                </ItemTitle>
+                <ItemDescription>
+                    Cursor updates per second, match your monitor's refresh rate. 0 sends every move
+                </ItemDescription>
+            </ItemContent>
+            <ItemActions>
+                <NumberInput
+                    className="w-32 h-8"
+                    value={mouseMoveRate}
+                    onChange={rate => {
+                        setMouseMoveRate(rate);
+                        invoke('set_mouse_move_rate', { rate });
+                    }}
+                    minValue={0}
+                    maxValue={360}
+                />
+            </ItemActions>
+        </Item>
     </div>;
 }
\ No newline at end of file
@@ -0,0 +1,33 @@
+import { useKeyEvent } from "@/stores/key_event";
+import { useKeyStyle } from "@/stores/key_style";
+
+// typed text, newest on the right, older characters scroll off the left
+export const TypingTicker = () => {
+    const typed = useKeyEvent(state => state.typed);
+
+    const text = useKeyStyle(state => state.text);
+    const border = useKeyStyle(state => state.border);
+    const background = useKeyStyle(state => state.background);
+
+    if (!typed) return null;
+
+    const tickerStyle = {
+        maxWidth: "100%",
+        overflow: "hidden",
+        whiteSpace: "pre",
+        direction: "rtl",
+        fontSize: text.size * 0.8,
+        color: text.color,
+        ...(background.enabled && {
+            paddingInline: text.size * 0.4,
+            paddingBlock: text.size * 0.25,
+            background: background.color,
+            borderRadius: border.radius * (text.size * 1.75),
+        }),
+    } as const;
+
+    // the bidi isolate keeps rtl scrolling from reordering the text
+    return <div style={tickerStyle}>
+        <bdi dir="ltr">{typed.replace(/\n/g, "⏎ ")}</bdi>
+    </div>;
+};
@@ -1,4 +1,4 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
 
 import { AboutPage, AppearanceSettings, GeneralSettings, KeycapSettings, MouseSettings } from "@/components/settings";
 import { VERSION } from "@/components/settings/about";
@@ -6,7 +6,14 @@ import { ThemeModeToggle } from "@/components/theme-mode-toggle";
 import { ScrollArea } from "@/components/ui/scroll-area";
 import { Separator } from "@/components/ui/separator";
 import { SidebarItem } from "@/components/ui/sidebar-item";
+import { KEY_EVENT_STORE, KeyEventStore, useKeyEvent } from "@/stores/key_event";
+import { KEY_STYLE_STORE, KeyStyleStore, useKeyStyle } from "@/stores/key_style";
+import { listenForUpdates } from "@/stores/sync";
+import { ListenerStatus } from "@/types/event";
 import { ComputerIcon, InformationSquareIcon, KeyboardIcon, Mouse09Icon, Settings03Icon } from "@hugeicons/core-free-icons";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { toast } from "sonner";
 
 const sideBar = [
     { title: "General", icon: Settings03Icon },
@@ -15,9 +22,35 @@ const sideBar = [
     { title: "Mouse", icon: Mouse09Icon },
 ]
 
+// tell the user why nothing is being visualized
+function showListenerStatus(status: ListenerStatus) {
+    if (status.state !== "failed") {
+        toast.dismiss("listener-status");
+        return;
+    }
+    toast.error(status.diagnostic.message, {
+        id: "listener-status",
+        description: `${status.diagnostic.hint}. Retrying in ${Math.round(status.retry_in_ms / 1000)}s.`,
+        duration: Infinity,
+    });
+}
+
 const Settings = () => {
     const [activeTab, setActiveTab] = useState(sideBar[0].title);
 
+    useEffect(() => {
+        invoke<ListenerStatus>("get_listener_status").then(showListenerStatus);
+        const unlistenPromises = [
+            listen<ListenerStatus>("listener-status", (event) => showListenerStatus(event.payload)),
+            // settings changed from the control socket
+            listenForUpdates<KeyEventStore>(KEY_EVENT_STORE, useKeyEvent.setState),
+            listenForUpdates<KeyStyleStore>(KEY_STYLE_STORE, useKeyStyle.setState),
+        ];
+        return () => {
+            unlistenPromises.forEach((p) => p.then((f) => f()));
+        };
+    }, []);
+
     return (
         <div className="flex w-screen h-screen overflow-hidden border-t bg-background">
             <div className="w-44 p-2 flex flex-col gap-y-1 rounded-xl">
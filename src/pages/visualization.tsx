@@ -1,25 +1,58 @@
+import { ArrangeOverlay } from "@/components/arrange-overlay";
 import { KeyOverlay } from "@/components/key-overlay";
 import { MouseOverlay } from "@/components/mouse-overlay";
 import { KEY_EVENT_STORE, KeyEventStore, useKeyEvent } from "@/stores/key_event";
-import { KEY_STYLE_STORE, KeyStyleStore, useKeyStyle } from '@/stores/key_style';
+import { FOLLOW_CURSOR, KEY_STYLE_STORE, KeyStyleStore, useKeyStyle } from '@/stores/key_style';
 import { listenForUpdates } from '@/stores/sync';
-import { EventPayload } from "@/types/event";
+import { THEME_ASSETS_CHANGED, useThemeAssets } from '@/stores/theme_assets';
+import { ResyncPayload, SequencedEvent } from "@/types/event";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { useEffect, useState, } from "react";
 
-export function Visualization() {
+// sequence number of the last applied input event
+let lastSeq = 0;
+
+async function resync() {
+  try {
+    const payload = await invoke<ResyncPayload>("resync");
+    lastSeq = Math.max(lastSeq, payload.seq);
+    useKeyEvent.getState().resync(payload);
+  } catch (error) {
+    console.error("Failed to resync:", error);
+  }
+}
+
+function onEvents(events: SequencedEvent[]) {
+  const { onEvent } = useKeyEvent.getState();
+  let gap = false;
+
+  for (const event of events) {
+    // already covered by a resync
+    if (event.seq <= lastSeq) continue;
+    if (lastSeq !== 0 && event.seq !== lastSeq + 1) gap = true;
+    lastSeq = event.seq;
+    onEvent(event);
+  }
+  // some events were lost, ask the backend for the real state
+  if (gap) resync();
+}
+
+// mouseOnly: extra overlay on another monitor when spanning monitors
+export function Visualization({ mouseOnly = false }: { mouseOnly?: boolean }) {
   const monitor = useKeyStyle((state) => state.appearance.monitor);
-  const onEvent = useKeyEvent((state) => state.onEvent);
+  const spanMonitors = useKeyStyle((state) => state.appearance.spanMonitors);
   const tick = useKeyEvent((state) => state.tick);
 
   // listening for input events
   const [isListening, setIsListening] = useState(true);
+  // dragging the keystrokes into place
+  const [interactive, setInteractive] = useState(false);
 
   useEffect(() => {
     const unlistenPromises = [
       // ───────────── input event listener ─────────────
-      listen<EventPayload>("input-event", (event) => onEvent(event.payload)),
+      listen<SequencedEvent[]>("input-events", (event) => onEvents(event.payload)),
       // ───────────── store sync ─────────────
       listenForUpdates<KeyEventStore>(KEY_EVENT_STORE, useKeyEvent.setState),
       listenForUpdates<KeyStyleStore>(KEY_STYLE_STORE, useKeyStyle.setState),
@@ -29,7 +62,12 @@ export function Visualization() {
       }),
       // ───────────── listener toggle ─────────────
       listen<boolean>("listening-toggle", (event) => setIsListening(event.payload)),
+      // ───────────── interactive mode ─────────────
+      listen<boolean>("interactive-mode", (event) => setInteractive(event.payload)),
+      // ───────────── imported fonts and icons ─────────────
+      listen(THEME_ASSETS_CHANGED, () => useThemeAssets.getState().load()),
     ];
+    useThemeAssets.getState().load();
     const id = setInterval(tick, 250);
 
     return () => {
@@ -40,9 +78,12 @@ export function Visualization() {
 
   useEffect(() => {
     const set_monitor = async () => {
-      if (!monitor) return;
+      if (mouseOnly || !monitor) return;
       try {
-        await invoke("set_main_window_monitor", { monitorName: monitor });
+        await invoke("set_follow_cursor", { enabled: monitor === FOLLOW_CURSOR });
+        if (monitor !== FOLLOW_CURSOR) {
+          await invoke("set_main_window_monitor", { monitorName: monitor });
+        }
       } catch (error) {
         console.error("Failed to set monitor:", error);
       }
@@ -50,10 +91,22 @@ export function Visualization() {
     set_monitor();
   }, [monitor]);
 
+  useEffect(() => {
+    if (mouseOnly) return;
+    invoke("set_span_monitors", { enabled: !!spanMonitors }).catch((error) => {
+      console.error("Failed to span monitors:", error);
+    });
+  }, [spanMonitors]);
+
+  if (interactive && !mouseOnly) {
+    return <div className="w-screen h-screen relative overflow-hidden">
+      <ArrangeOverlay />
+    </div>;
+  }
   if (!isListening) return null;
 
   return <div className="w-screen h-screen relative overflow-hidden">
     <MouseOverlay />
-    <KeyOverlay />
+    {!mouseOnly && <KeyOverlay />}
   </div>;
 }
\ No newline at end of file
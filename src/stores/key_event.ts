@@ -1,4 +1,4 @@
-import { EventPayload, KeyEvent, MappedKeys, MODIFIERS, MouseButton, MouseButtonEvent, MouseMoveEvent, MouseWheelEvent, RawKey, RawKeyEvent } from "@/types/event";
+import { ComboEvent, EventPayload, KeyEvent, MappedKeys, MODIFIERS, MouseButton, MouseButtonEvent, MouseMoveEvent, MouseWheelEvent, RawKey, RawKeyEvent, ResyncPayload, SequenceEvent, TextEvent } from "@/types/event";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 import { createJSONStorage, persist } from "zustand/middleware";
 import { tauriStorage } from "./storage";
@@ -7,10 +7,39 @@ import { createSyncedStore } from "./sync";
 
 export const KEY_EVENT_STORE = "key_event_store";
 const SCROLL_LINGER_MS = 300;
+// characters kept in the typing ticker
+const MAX_TYPED_LENGTH = 48;
+// password managers, typed text never shows while they are focused
+export const DEFAULT_PRIVATE_APPS = ["1Password", "Bitwarden", "Dashlane", "KeePass", "KeePassXC", "Keychain Access"];
+// dead keys on Linux come out as a letter plus a combining mark
+const COMBINING_MARK = /\p{M}/u;
+
+// screen recorders: the executable, then words its arguments must contain
+export const DEFAULT_RECORDERS = [
+    "obs", "obs64", "simplescreenrecorder", "ffmpeg x11grab", "ffmpeg gdigrab",
+    "ffmpeg avfoundation", "gpu-screen-recorder", "wf-recorder", "kazam", "peek",
+];
+
+// visualize only while one of these holds, see `set_activation_rules`
+export interface ActivationRules {
+    enabled: boolean;
+    processes: string[];
+    apps: string[];
+    // local time, "HH:MM"
+    from?: string;
+    to?: string;
+}
+
+export interface KeySequence {
+    // steps separated by spaces, e.g. "Ctrl+X Ctrl+S"
+    keys: string;
+    name: string;
+}
 
 interface KeyGroup {
     keys: KeyEvent[];
     createdAt: number;
+    description?: string;
 }
 
 export interface KeyEventState {
@@ -28,19 +57,39 @@ export interface KeyEventState {
     // ───────────── visual state ─────────────
     settingsOpen: boolean;
     groups: KeyGroup[];
+    typed: string;
+    typedAt: number;
     // ───────────── config ─────────────
     dragThreshold: number;
+    // mouse moves sent per second, 0 sends every one
+    mouseMoveRate: number;
     filter: "none" | "modifiers" | "custom";
     allowedKeys: string[];
     showEventHistory: boolean;
     maxHistory: number;
     lingerDurationMs: number;
     toggleShortcut: string[];
+    // lets the overlay take the cursor to drag the keystrokes around
+    arrangeShortcut: string[];
+    showKeyTiming: boolean;
+    // release keys held longer than this, in case their release got lost, 0 never does
+    stuckKeyTimeoutSecs: number;
+    // pause after this long without input, 0 never does
+    idleTimeoutSecs: number;
+    hideWhenIdle: boolean;
+    activationRules: ActivationRules;
+    collectStatistics: boolean;
+    showAnnotations: boolean;
+    sequences: KeySequence[];
+    sequenceTimeoutMs: number;
+    typingMode: boolean;
+    privateApps: string[];
 }
 
 interface KeyEventActions {
     // ───────────── setters ─────────────
     setDragThreshold(value: KeyEventState["dragThreshold"]): void;
+    setMouseMoveRate(value: KeyEventState["mouseMoveRate"]): void;
     setFilter(value: KeyEventState["filter"]): void;
     setAllowedKeys(keys: KeyEventState["allowedKeys"]): void;
     setShowEventHistory(value: KeyEventState["showEventHistory"]): void;
@@ -48,15 +97,32 @@ interface KeyEventActions {
     // setShowMouseEvents(value: KeyEventState["showMouseEvents"]): void;
     setLingerDurationMs(value: KeyEventState["lingerDurationMs"]): void;
     setToggleShortcut(value: KeyEventState["toggleShortcut"]): void;
+    setArrangeShortcut(value: KeyEventState["arrangeShortcut"]): void;
+    setShowKeyTiming(value: KeyEventState["showKeyTiming"]): void;
+    setStuckKeyTimeoutSecs(value: KeyEventState["stuckKeyTimeoutSecs"]): void;
+    setIdleTimeoutSecs(value: KeyEventState["idleTimeoutSecs"]): void;
+    setHideWhenIdle(value: KeyEventState["hideWhenIdle"]): void;
+    setActivationRules(value: KeyEventState["activationRules"]): void;
+    setCollectStatistics(value: KeyEventState["collectStatistics"]): void;
+    setShowAnnotations(value: KeyEventState["showAnnotations"]): void;
+    setSequences(value: KeyEventState["sequences"]): void;
+    setSequenceTimeoutMs(value: KeyEventState["sequenceTimeoutMs"]): void;
+    setTypingMode(value: KeyEventState["typingMode"]): void;
+    setPrivateApps(value: KeyEventState["privateApps"]): void;
     // ───────────── event actions ─────────────
     onEvent(event: EventPayload): void;
     onKeyPress(event: RawKeyEvent): void;
     ignoreEvent(pressedKeys: string[]): boolean;
     onKeyRelease(event: RawKeyEvent): void;
+    onKeyRepeat(event: RawKeyEvent): void;
     onMouseMove(event: MouseMoveEvent): void;
     onMouseButtonPress(event: MouseButtonEvent): void;
     onMouseButtonRelease(event: MouseButtonEvent): void;
     onMouseWheel(event: MouseWheelEvent): void;
+    onCombo(event: ComboEvent): void;
+    onSequence(event: SequenceEvent): void;
+    onText(event: TextEvent): void;
+    resync(payload: ResyncPayload): void;
     tick(): void;
 }
 
@@ -69,9 +135,12 @@ const createKeyEventStore = createSyncedStore<KeyEventStore>(
         pressedMouseButton: null,
         mouse: { x: 0, y: 0, wheel: 0, dragging: false },
         groups: <KeyGroup[]>[],
+        typed: "",
+        typedAt: 0,
         listening: true,
         settingsOpen: false,
         dragThreshold: 50,
+        mouseMoveRate: 60,
         filter: "modifiers",
         allowedKeys: [
             RawKey.ControlLeft,
@@ -82,10 +151,25 @@ const createKeyEventStore = createSyncedStore<KeyEventStore>(
         maxHistory: 5,
         lingerDurationMs: 5_000,
         toggleShortcut: [RawKey.ShiftLeft, RawKey.F10],
+        arrangeShortcut: [RawKey.ShiftLeft, RawKey.F9],
+        showKeyTiming: false,
+        stuckKeyTimeoutSecs: 0,
+        idleTimeoutSecs: 0,
+        hideWhenIdle: false,
+        activationRules: { enabled: false, processes: DEFAULT_RECORDERS, apps: [] },
+        collectStatistics: false,
+        showAnnotations: false,
+        sequences: <KeySequence[]>[],
+        sequenceTimeoutMs: 1000,
+        typingMode: false,
+        privateApps: DEFAULT_PRIVATE_APPS,
 
         setDragThreshold(value: number) {
             set({ dragThreshold: value });
         },
+        setMouseMoveRate(value: number) {
+            set({ mouseMoveRate: value });
+        },
         setFilter(value: "none" | "modifiers" | "custom") {
             set({ filter: value });
         },
@@ -104,12 +188,50 @@ const createKeyEventStore = createSyncedStore<KeyEventStore>(
         setToggleShortcut(value: string[]) {
             set({ toggleShortcut: value });
         },
+        setArrangeShortcut(value: string[]) {
+            set({ arrangeShortcut: value });
+        },
+        setShowKeyTiming(value: boolean) {
+            set({ showKeyTiming: value });
+        },
+        setStuckKeyTimeoutSecs(value: number) {
+            set({ stuckKeyTimeoutSecs: value });
+        },
+        setIdleTimeoutSecs(value: number) {
+            set({ idleTimeoutSecs: value });
+        },
+        setHideWhenIdle(value: boolean) {
+            set({ hideWhenIdle: value });
+        },
+        setActivationRules(value: ActivationRules) {
+            set({ activationRules: value });
+        },
+        setCollectStatistics(value: boolean) {
+            set({ collectStatistics: value });
+        },
+        setShowAnnotations(value: boolean) {
+            set({ showAnnotations: value });
+        },
+        setSequences(value: KeySequence[]) {
+            set({ sequences: value });
+        },
+        setSequenceTimeoutMs(value: number) {
+            set({ sequenceTimeoutMs: value });
+        },
+        setTypingMode(value: boolean) {
+            set({ typingMode: value, typed: "" });
+        },
+        setPrivateApps(value: string[]) {
+            set({ privateApps: value });
+        },
         onEvent(event: EventPayload) {
             const state = get();
             switch (event.type) {
                 case "KeyEvent":
                     if (!MappedKeys.has(event.name)) return;
-                    if (event.pressed) {
+                    if (event.repeat) {
+                        state.onKeyRepeat(event);
+                    } else if (event.pressed) {
                         state.onKeyPress(event);
                     } else {
                         state.onKeyRelease(event);
@@ -131,6 +253,18 @@ const createKeyEventStore = createSyncedStore<KeyEventStore>(
                 case "MouseWheelEvent":
                     state.onMouseWheel(event);
                     break;
+
+                case "ComboEvent":
+                    state.onCombo(event);
+                    break;
+
+                case "SequenceEvent":
+                    state.onSequence(event);
+                    break;
+
+                case "TextEvent":
+                    state.onText(event);
+                    break;
             }
         },
         onKeyPress(event: RawKeyEvent) {
@@ -206,6 +340,11 @@ const createKeyEventStore = createSyncedStore<KeyEventStore>(
             if (state.showEventHistory && groups.length > state.maxHistory) {
                 groups = groups.slice(groups.length - state.maxHistory);
             }
+            // the group changed, a following ComboEvent labels it again
+            const changed = groups.length - 1;
+            if (groups[changed].description) {
+                groups[changed] = { ...groups[changed], description: undefined };
+            }
 
             set({ pressedKeys, groups });
         },
@@ -228,14 +367,26 @@ const createKeyEventStore = createSyncedStore<KeyEventStore>(
             const groups = [...state.groups];
             const last = groups.length - 1;
 
-            const kIndex = last >= 0 ? groups[last].keys.findIndex(key => key.name === event.name) : undefined;
-            if (kIndex && kIndex >= 0) {
+            const kIndex = last >= 0 ? groups[last].keys.findIndex(key => key.name === event.name) : -1;
+            if (kIndex >= 0) {
                 groups[last].keys[kIndex].lastPressedAt = Date.now();
+                groups[last].keys[kIndex].heldMs = event.held_ms ?? undefined;
                 set({ pressedKeys, groups });
             } else {
                 set({ pressedKeys });
             }
         },
+        onKeyRepeat(event: RawKeyEvent) {
+            const state = get();
+            if (!state.showKeyTiming) return;
+
+            const groups = [...state.groups];
+            const last = groups.length - 1;
+            const key = last >= 0 ? groups[last].keys.find(key => key.name === event.name) : undefined;
+            if (!key) return;
+            key.repeat(event.held_ms);
+            set({ groups });
+        },
         onMouseMove(event: MouseMoveEvent) {
             const state = get();
             const mouse = { ...state.mouse };
@@ -320,7 +471,7 @@ const createKeyEventStore = createSyncedStore<KeyEventStore>(
         onMouseWheel(event: MouseWheelEvent) {
             // bug: history mode, ctrl + scroll, scroll
             const state = get();
-            const wheel = Math.sign(event.delta_y);
+            const wheel = Math.sign(event.precise_delta_y);
 
             if (wheel === 0) return;
 
@@ -348,6 +499,81 @@ const createKeyEventStore = createSyncedStore<KeyEventStore>(
 
             set({ mouse });
         },
+        onCombo(event: ComboEvent) {
+            const state = get();
+            if (!state.showAnnotations) return;
+
+            // label the group showing this combo, unless it was filtered out
+            const groups = [...state.groups];
+            const last = groups.length - 1;
+            if (last < 0) return;
+            const names = groups[last].keys.map(key => key.name);
+            if (!event.keys.every(name => names.includes(name))) return;
+
+            groups[last] = { ...groups[last], description: event.description };
+            set({ groups });
+        },
+        onSequence(event: SequenceEvent) {
+            const state = get();
+            let groups = [...state.groups];
+            const last = groups.length - 1;
+            if (last < 0) return;
+
+            // history mode, fold the groups of the earlier steps into the last one
+            if (state.showEventHistory) {
+                let first = last;
+                for (const step of event.steps.slice(0, -1).reverse()) {
+                    const previous = groups[first - 1];
+                    if (!previous || !previous.keys.every(key => step.includes(key.name))) break;
+                    first--;
+                }
+                groups = [...groups.slice(0, first), groups[last]];
+            }
+            const folded = groups.length - 1;
+            groups[folded] = { ...groups[folded], description: event.name };
+            set({ groups });
+        },
+        onText(event: TextEvent) {
+            const state = get();
+            if (!state.typingMode) return;
+
+            const characters = Array.from(state.typed);
+            if (event.backspace) {
+                while (characters.length && COMBINING_MARK.test(characters[characters.length - 1])) {
+                    characters.pop();
+                }
+                characters.pop();
+            } else {
+                characters.push(...Array.from(event.text));
+            }
+            const typed = characters.slice(-MAX_TYPED_LENGTH).join("");
+
+            // the key typed text, the ticker shows it instead of a keycap
+            let groups = state.groups;
+            const last = groups.length - 1;
+            if (last >= 0 && groups[last].keys.every(key => key.name === event.key || key.name.startsWith("Shift"))) {
+                groups = groups.slice(0, last);
+            }
+            set({ typed, typedAt: Date.now(), groups });
+        },
+        resync(payload: ResyncPayload) {
+            const state = get();
+            const pressedKeys = [
+                ...payload.pressed_keys.filter(name => MappedKeys.has(name)),
+                ...payload.pressed_buttons.map(button => button.toString()),
+            ];
+            const pressedMouseButton = payload.pressed_buttons[0] ?? null;
+            const mouse = {
+                ...state.mouse,
+                x: payload.mouse_x,
+                y: payload.mouse_y,
+            };
+            if (!pressedMouseButton) {
+                mouse.dragging = false;
+                mouse.dragStart = undefined;
+            }
+            set({ pressedKeys, pressedMouseButton, mouse });
+        },
         tick() {
             // todo: remove pressed keys with unsually long linger duration
             const state = get();
@@ -370,6 +596,10 @@ const createKeyEventStore = createSyncedStore<KeyEventStore>(
             // don't remove keys while styling
             if (state.settingsOpen) return;
 
+            if (state.typed && now - state.typedAt > state.lingerDurationMs) {
+                set({ typed: "" });
+            }
+
             // remove keys that have exceeded linger duration
             for (const group of state.groups) {
                 const updatedKeys = group.keys.filter((key) => {
@@ -398,7 +628,7 @@ const createKeyEventStore = createSyncedStore<KeyEventStore>(
         name: KEY_EVENT_STORE,
         storage: createJSONStorage(() => tauriStorage),
         partialize: (state) => {
-            const { pressedKeys, pressedMouseButton, mouse, groups, settingsOpen, ...persistedState } = state;
+            const { pressedKeys, pressedMouseButton, mouse, groups, typed, typedAt, settingsOpen, ...persistedState } = state;
             return persistedState;
         },
     }),
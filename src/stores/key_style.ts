@@ -3,14 +3,23 @@ import { createJSONStorage, persist } from "zustand/middleware";
 import { tauriStorage } from "./storage";
 import { createSyncedStore } from "./sync";
 import { getCurrentWindow } from "@tauri-apps/api/window";
-import { readTextFile, writeTextFile } from "@tauri-apps/plugin-fs";
+import { invoke } from "@tauri-apps/api/core";
 import { open, save } from "@tauri-apps/plugin-dialog";
 import { toast } from "sonner";
 
 export const KEY_STYLE_STORE = "key_style_store";
+// appearance.monitor value that moves the overlay along with the pointer
+export const FOLLOW_CURSOR = "follow-cursor";
+// .keyviz-theme bundles the theme with its fonts and icons
+const THEME_EXPORT_FILTERS = [
+    { name: "Theme", extensions: ["json"] },
+    { name: "Theme with Fonts and Icons", extensions: ["keyviz-theme"] },
+];
 
 export interface AppearanceSettings {
     monitor: string | null;
+    // mouse-only overlays on the other monitors
+    spanMonitors: boolean;
     flexDirection: "row" | "column";
     alignment: Alignment;
     marginX: number;
@@ -101,9 +110,10 @@ export type KeyStyleStore = KeyStyleState & KeyStyleActions;
 
 const createKeyStyleStore = createSyncedStore<KeyStyleStore>(
     KEY_STYLE_STORE,
-    (set, get) => ({
+    (set) => ({
         appearance: {
             monitor: null,
+            spanMonitors: false,
             flexDirection: "column",
             alignment: "bottom-center",
             marginX: 100,
@@ -172,34 +182,11 @@ const createKeyStyleStore = createSyncedStore<KeyStyleStore>(
             try {
                 const filePath = await open({
                     multiple: false,
-                    filters: [{
-                        name: 'JSON Files',
-                        extensions: ['json']
-                    }]
+                    filters: [{ name: "Themes", extensions: ["json", "keyviz-theme"] }],
                 });
                 if (!filePath || typeof filePath !== 'string') return;
-
-                const content = await readTextFile(filePath);
-                const parsedData: KeyStyleState = JSON.parse(content);
-
-                if (
-                    !parsedData.appearance || !parsedData.layout || !parsedData.color ||
-                    !parsedData.modifier || !parsedData.text || !parsedData.border ||
-                    !parsedData.background || !parsedData.mouse
-                ) {
-                    toast.warning("Invalid file format", { description: filePath });
-                    return;
-                }
-                set(() => ({
-                    appearance: parsedData.appearance,
-                    layout: parsedData.layout,
-                    color: parsedData.color,
-                    modifier: parsedData.modifier,
-                    text: parsedData.text,
-                    border: parsedData.border,
-                    background: parsedData.background,
-                    mouse: parsedData.mouse,
-                }));
+                // validated and applied by the backend, which syncs the stores back
+                await invoke("import_theme", { path: filePath });
                 toast.success("Imported successfully", { description: filePath });
             } catch (err) {
                 toast.error("Error importing file", {
@@ -208,24 +195,13 @@ const createKeyStyleStore = createSyncedStore<KeyStyleStore>(
             }
         },
         export: async () => {
-            const state = get();
-            const exportData: KeyStyleState = {
-                appearance: state.appearance,
-                layout: state.layout,
-                color: state.color,
-                modifier: state.modifier,
-                text: state.text,
-                border: state.border,
-                background: state.background,
-                mouse: state.mouse,
-            };
             try {
                 const filePath = await save({
                     defaultPath: "key_style.json",
-                    filters: [{ name: "JSON Files", extensions: ["json"] }],
+                    filters: THEME_EXPORT_FILTERS,
                 });
                 if (!filePath) return;
-                await writeTextFile(filePath, JSON.stringify(exportData, null, 2));
+                await invoke("export_theme", { path: filePath });
                 toast.success("Exported successfully", { description: filePath });
             } catch (err) {
                 toast.error("Error exporting file", {
@@ -0,0 +1,37 @@
+import { invoke } from "@tauri-apps/api/core";
+import { create } from "zustand";
+
+// family the imported theme's font is registered under
+export const THEME_FONT = "keyviz-theme";
+export const THEME_ASSETS_CHANGED = "theme-assets-changed";
+
+// fonts and icons of the imported .keyviz-theme, as data URLs
+interface ThemeAssets {
+    font: string | null;
+    icons: Record<string, string>;
+}
+
+interface ThemeAssetsStore extends ThemeAssets {
+    load: () => Promise<void>;
+}
+
+let fontFace: FontFace | null = null;
+
+export const useThemeAssets = create<ThemeAssetsStore>()((set) => ({
+    font: null,
+    icons: {},
+    load: async () => {
+        try {
+            const assets = await invoke<ThemeAssets>("get_theme_assets");
+            if (fontFace) document.fonts.delete(fontFace);
+            fontFace = null;
+            if (assets.font) {
+                fontFace = await new FontFace(THEME_FONT, `url(${assets.font})`).load();
+                document.fonts.add(fontFace);
+            }
+            set(assets);
+        } catch (error) {
+            console.error("Failed to load theme assets:", error);
+        }
+    },
+}));
@@ -0,0 +1,21 @@
+// environment report returned by the `diagnose` command
+export interface DiagnosticCheck {
+  name: string;
+  status: "ok" | "warn" | "fail";
+  detail: string;
+}
+
+export interface DiagnosticReport {
+  os: string;
+  display_server: "x11" | "wayland" | "windows" | "macos" | "unknown";
+  backend: string;
+  checks: DiagnosticCheck[];
+}
+
+// returned by `get_listener_metrics`, counted since startup
+export interface ListenerMetrics {
+  mouse_moves_emitted: number;
+  mouse_moves_dropped: number;
+  events_queued: number;
+  events_overflowed: number;
+}
@@ -2,12 +2,45 @@ export type EventPayload =
   | RawKeyEvent
   | MouseButtonEvent
   | MouseMoveEvent
-  | MouseWheelEvent;
+  | MouseWheelEvent
+  | ComboEvent
+  | SequenceEvent
+  | TextEvent;
+
+// events arrive in batches, numbered so gaps can be detected
+export type SequencedEvent = EventPayload & {
+  seq: number;
+  time: number;
+};
+
+// authoritative input state returned by the `resync` command
+export interface ResyncPayload {
+  seq: number;
+  listening: boolean;
+  pressed_keys: string[];
+  pressed_buttons: MouseButton[];
+  mouse_x: number;
+  mouse_y: number;
+}
+
+export interface ListenerDiagnostic {
+  code: string;
+  message: string;
+  hint: string;
+}
+
+export type ListenerStatus =
+  | { state: "starting" | "running" }
+  | { state: "failed"; diagnostic: ListenerDiagnostic; attempt: number; retry_in_ms: number };
 
 export interface RawKeyEvent {
   type: "KeyEvent";
   pressed: boolean;
   name: string;
+  // how long the key was down, on release and repeats
+  held_ms?: number | null;
+  // OS auto-repeat of a key already down
+  repeat?: boolean;
 }
 
 export interface MouseButtonEvent {
@@ -26,6 +59,31 @@ export interface MouseWheelEvent {
   type: "MouseWheelEvent";
   delta_x: number;
   delta_y: number;
+  // fractional notches from high-resolution wheels and trackpads
+  precise_delta_x: number;
+  precise_delta_y: number;
+}
+
+// what the combo just pressed does, from the annotation files
+export interface ComboEvent {
+  type: "ComboEvent";
+  keys: string[];
+  description: string;
+}
+
+// a configured key sequence like `g g` completed, one key list per step
+export interface SequenceEvent {
+  type: "SequenceEvent";
+  steps: string[][];
+  name: string;
+}
+
+// text typed by `key`, or one character erased when `backspace` is set
+export interface TextEvent {
+  type: "TextEvent";
+  key: string;
+  text: string;
+  backspace: boolean;
 }
 
 export type MouseButton =
@@ -200,16 +258,27 @@ export class KeyEvent {
   name: string;
   pressedCount: number;
   lastPressedAt: number;
+  repeatCount: number;
+  heldMs?: number;
 
   constructor(name: string) {
     this.name = name;
     this.pressedCount = 1;
     this.lastPressedAt = Date.now();
+    this.repeatCount = 0;
   }
 
   press() {
     this.pressedCount += 1;
     this.lastPressedAt = Date.now();
+    this.repeatCount = 0;
+    this.heldMs = undefined;
+  }
+
+  repeat(heldMs?: number | null) {
+    this.repeatCount += 1;
+    this.lastPressedAt = Date.now();
+    this.heldMs = heldMs ?? undefined;
   }
 
   isModifier(): boolean {
@@ -0,0 +1,19 @@
+// aggregates returned by the `get_statistics` command
+export interface DayStats {
+  keys: Record<string, number>;
+  combos: Record<string, number>;
+  clicks: Record<string, number>;
+  apps: Record<string, number>;
+  // keys and combos per foreground app
+  app_usage: Record<string, AppStats>;
+}
+
+export interface AppStats {
+  keys: Record<string, number>;
+  combos: Record<string, number>;
+}
+
+export interface Statistics {
+  // keyed by UTC date, e.g. "2024-05-01"
+  days: Record<string, DayStats>;
+}